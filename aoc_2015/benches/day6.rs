@@ -0,0 +1,69 @@
+//! Times the part one grids of day 6 on the same random commands:
+//! `cargo bench --bench day6`.
+
+use std::time::Duration;
+
+use aoc_2015::registry::{self, Solution};
+
+const COMMANDS: usize = 300;
+const RUNS: usize = 11;
+
+/// `count` commands over the whole grid, from a xorshift generator.
+fn random_commands(mut seed: u64, count: usize) -> String {
+    let mut next = |bound: usize| {
+        seed ^= seed << 13;
+        seed ^= seed >> 7;
+        seed ^= seed << 17;
+        (seed >> 24) as usize % bound
+    };
+    (0..count)
+        .map(|_| {
+            let instruction = ["turn on", "turn off", "toggle"][next(3)];
+            let (x0, x1) = (next(1000), next(1000));
+            let (y0, y1) = (next(1000), next(1000));
+            format!(
+                "{} {},{} through {},{}",
+                instruction,
+                x0.min(x1),
+                y0.min(y1),
+                x0.max(x1),
+                y0.max(y1)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// The answer of `solution` and its median runtime over [`RUNS`] runs.
+fn measure(solution: &Solution, input: &str) -> (String, Duration) {
+    let mut answer = String::new();
+    let mut times: Vec<_> = (0..RUNS)
+        .map(|_| {
+            let outcome = solution.run(input).unwrap_or_else(|e| panic!("{}", e));
+            answer = outcome.answer;
+            outcome.runner
+        })
+        .collect();
+    times.sort();
+    (answer, times[RUNS / 2])
+}
+
+fn main() {
+    let input = random_commands(0x2015_0006, COMMANDS);
+    let mut answers = Vec::new();
+    println!(
+        "day 6 part 1, {} random commands, median of {} runs",
+        COMMANDS, RUNS
+    );
+    for solution in registry::solutions_of(6).filter(|s| s.part == 1) {
+        let (answer, time) = measure(solution, &input);
+        println!(
+            "{:<10} {:>12.3?}",
+            solution.variant.unwrap_or("Lights"),
+            time
+        );
+        answers.push(answer);
+    }
+    answers.dedup();
+    assert_eq!(answers.len(), 1, "the grids disagree: {:?}", answers);
+}
@@ -0,0 +1,414 @@
+//! Life-like cellular automata on a [`Grid`] of booleans.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use aoc_2015::{
+//!     automaton::{Automaton, Cycle, Rule},
+//!     grid::Grid,
+//! };
+//!
+//! let blinker = Grid::parse_with(".....\n..#..\n..#..\n..#..\n.....", |c| c == '#').unwrap();
+//! let mut life = Automaton::new(blinker, "B3/S23".parse().unwrap());
+//!
+//! life.step();
+//! assert_eq!(life.render(), "Generation 1:\n.....\n.....\n.###.\n.....\n.....");
+//! assert_eq!(life.find_cycle(10), Some(Cycle { start: 1, period: 2 }));
+//! assert_eq!(life.rule(), Rule::CONWAY);
+//! ```
+
+use std::{
+    collections::HashMap,
+    fmt,
+    hash::{DefaultHasher, Hash, Hasher},
+    str::FromStr,
+};
+
+use derive_more::derive::{Display, Error};
+use rayon::prelude::*;
+
+use crate::grid::Grid;
+
+/// Which numbers of live neighbours bring a dead cell to life (birth), and
+/// which keep a live cell alive (survival), as bit sets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rule {
+    birth: u16,
+    survival: u16,
+}
+
+impl Rule {
+    /// Conway's Game of Life, `B3/S23`.
+    pub const CONWAY: Rule = Rule {
+        birth: 1 << 3,
+        survival: 1 << 2 | 1 << 3,
+    };
+
+    /// Whether a cell is alive in the next generation.
+    pub fn next(self, alive: bool, neighbours: u32) -> bool {
+        let counts = if alive { self.survival } else { self.birth };
+        counts & (1 << neighbours) != 0
+    }
+}
+
+/// A rule not in `B3/S23` notation.
+#[derive(Debug, Clone, PartialEq, Eq, Display, Error)]
+#[display("invalid rule `{_0}`, expected B/S notation like B3/S23")]
+pub struct InvalidRule(#[error(not(source))] pub String);
+
+impl FromStr for Rule {
+    type Err = InvalidRule;
+
+    fn from_str(s: &str) -> Result<Self, InvalidRule> {
+        let counts = |digits: &str| {
+            digits
+                .chars()
+                .try_fold(0u16, |counts, c| match c.to_digit(10) {
+                    Some(n) if n <= 8 && counts & (1 << n) == 0 => Some(counts | 1 << n),
+                    _ => None,
+                })
+        };
+        let (birth, survival) = s
+            .split_once('/')
+            .and_then(|(birth, survival)| {
+                Some((birth.strip_prefix('B')?, survival.strip_prefix('S')?))
+            })
+            .and_then(|(birth, survival)| Some((counts(birth)?, counts(survival)?)))
+            .ok_or_else(|| InvalidRule(s.to_string()))?;
+        Ok(Rule { birth, survival })
+    }
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let digits = |counts: u16| {
+            (0..=8)
+                .filter(|n| counts & (1 << n) != 0)
+                .map(|n| n.to_string())
+                .collect::<String>()
+        };
+        write!(f, "B{}/S{}", digits(self.birth), digits(self.survival))
+    }
+}
+
+/// What the cells on the edges see past them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Boundary {
+    /// Dead cells.
+    Bounded,
+    /// The cells on the opposite edge. On grids less than 3 cells wide or
+    /// high, a cell may count as several of its own neighbours.
+    Toroidal,
+    /// Dead cells, as in [`Boundary::Bounded`], but the listed cells are
+    /// stuck on, whatever the rule says.
+    Fixed(Vec<(usize, usize)>),
+}
+
+impl Boundary {
+    /// The four corners of a `width` by `height` grid stuck on.
+    pub fn corners(width: usize, height: usize) -> Self {
+        let (right, bottom) = (width.saturating_sub(1), height.saturating_sub(1));
+        Boundary::Fixed(vec![(0, 0), (right, 0), (0, bottom), (right, bottom)])
+    }
+
+    /// The index `delta` away from `i` along an edge of length `len`, if
+    /// there is a cell there.
+    fn offset(&self, i: usize, delta: isize, len: usize) -> Option<usize> {
+        match self {
+            Boundary::Toroidal => Some((i as isize + delta).rem_euclid(len as isize) as usize),
+            Boundary::Bounded | Boundary::Fixed(_) => {
+                i.checked_add_signed(delta).filter(|&j| j < len)
+            }
+        }
+    }
+}
+
+/// The first generation repeating an earlier one, and how far back.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    /// The earlier generation.
+    pub start: u64,
+    pub period: u64,
+}
+
+/// A generation of cells and the rule making the next one.
+///
+/// Each step writes the next generation into a second grid, then swaps the
+/// two, so stepping never allocates.
+#[derive(Debug, Clone)]
+pub struct Automaton {
+    cells: Grid<bool>,
+    next: Grid<bool>,
+    rule: Rule,
+    boundary: Boundary,
+    parallel: bool,
+    generation: u64,
+}
+
+impl Automaton {
+    pub fn new(cells: Grid<bool>, rule: Rule) -> Self {
+        Self {
+            next: cells.clone(),
+            cells,
+            rule,
+            boundary: Boundary::Bounded,
+            parallel: false,
+            generation: 0,
+        }
+    }
+
+    /// Sets what lies past the edges, turning on any fixed cells right away.
+    pub fn boundary(mut self, boundary: Boundary) -> Self {
+        self.boundary = boundary;
+        self.fix_cells();
+        self
+    }
+
+    /// Steps the rows in parallel, which pays off on large grids only.
+    pub fn parallel(mut self, parallel: bool) -> Self {
+        self.parallel = parallel;
+        self
+    }
+
+    pub fn cells(&self) -> &Grid<bool> {
+        &self.cells
+    }
+
+    pub fn rule(&self) -> Rule {
+        self.rule
+    }
+
+    /// How many steps were taken.
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// The number of live cells.
+    pub fn alive(&self) -> usize {
+        self.cells.iter().filter(|&&alive| alive).count()
+    }
+
+    pub fn step(&mut self) {
+        let (cells, rule, boundary) = (&self.cells, self.rule, &self.boundary);
+        let update_row = |(y, row): (usize, &mut [bool])| {
+            for (x, next) in row.iter_mut().enumerate() {
+                *next = rule.next(cells[(x, y)], live_neighbours(cells, boundary, x, y));
+            }
+        };
+        if self.parallel {
+            self.next.par_rows_mut().enumerate().for_each(update_row);
+        } else {
+            self.next.rows_mut().enumerate().for_each(update_row);
+        }
+
+        std::mem::swap(&mut self.cells, &mut self.next);
+        self.fix_cells();
+        self.generation += 1;
+    }
+
+    pub fn run(&mut self, steps: u64) {
+        for _ in 0..steps {
+            self.step();
+        }
+    }
+
+    /// Steps until a generation hashes the same as an earlier one, stepping
+    /// at most `limit` times.
+    ///
+    /// A repeat is checked by running one more period on a copy before it is
+    /// reported, so a hash collision cannot pass for a cycle. The automaton
+    /// is left on the repeating generation.
+    pub fn find_cycle(&mut self, limit: u64) -> Option<Cycle> {
+        let mut seen = HashMap::new();
+        let end = self.generation + limit;
+        loop {
+            let hash = self.hash_cells();
+            if let Some(&start) = seen.get(&hash) {
+                let period = self.generation - start;
+                let mut copy = self.clone();
+                copy.run(period);
+                if copy.cells == self.cells {
+                    return Some(Cycle { start, period });
+                }
+            }
+            seen.insert(hash, self.generation);
+
+            if self.generation == end {
+                return None;
+            }
+            self.step();
+        }
+    }
+
+    /// The generation number, then the cells with `#` for alive and `.` for
+    /// dead.
+    pub fn render(&self) -> String {
+        let cells = self.cells.map(|&alive| if alive { '#' } else { '.' });
+        format!("Generation {}:\n{}", self.generation, cells)
+    }
+
+    fn fix_cells(&mut self) {
+        if let Boundary::Fixed(fixed) = &self.boundary {
+            for &(x, y) in fixed {
+                if let Some(cell) = self.cells.get_mut(x, y) {
+                    *cell = true;
+                }
+            }
+        }
+    }
+
+    fn hash_cells(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.cells.hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+fn live_neighbours(cells: &Grid<bool>, boundary: &Boundary, x: usize, y: usize) -> u32 {
+    let mut count = 0;
+    for dy in -1..=1 {
+        let Some(ny) = boundary.offset(y, dy, cells.height()) else {
+            continue;
+        };
+        let row = cells.row(ny);
+        for dx in -1..=1 {
+            if (dx, dy) == (0, 0) {
+                continue;
+            }
+            if boundary
+                .offset(x, dx, cells.width())
+                .is_some_and(|nx| row[nx])
+            {
+                count += 1;
+            }
+        }
+    }
+    count
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::*;
+
+    fn parse(map: &str) -> Grid<bool> {
+        Grid::parse_with(map, |c| c == '#').unwrap()
+    }
+
+    const EXAMPLE: &str = ".#.#.#\n...##.\n#....#\n..#...\n#.#..#\n####..";
+
+    #[rstest]
+    #[case("B3/S23")]
+    #[case("B36/S23")]
+    #[case("B/S012345678")]
+    #[case("B2/S")]
+    fn rule_roundtrip(#[case] rule: &str) {
+        assert_eq!(rule.parse::<Rule>().unwrap().to_string(), rule);
+    }
+
+    #[rstest]
+    #[case("")]
+    #[case("B3S23")]
+    #[case("S23/B3")]
+    #[case("B9/S23")]
+    #[case("B33/S23")]
+    #[case("B3/S2x")]
+    fn invalid_rules(#[case] rule: &str) {
+        assert_eq!(rule.parse::<Rule>(), Err(InvalidRule(rule.to_string())));
+    }
+
+    #[test]
+    fn example_part_one() {
+        let mut life = Automaton::new(parse(EXAMPLE), Rule::CONWAY);
+        life.run(4);
+        assert_eq!(
+            life.render(),
+            "Generation 4:\n......\n......\n..##..\n..##..\n......\n......"
+        );
+        assert_eq!(life.alive(), 4);
+    }
+
+    #[test]
+    fn example_part_two() {
+        let mut life =
+            Automaton::new(parse(EXAMPLE), Rule::CONWAY).boundary(Boundary::corners(6, 6));
+        assert!(life.cells()[(0, 0)]);
+        life.run(5);
+        assert_eq!(
+            life.render(),
+            "Generation 5:\n##.###\n.##..#\n.##...\n.##...\n#.#...\n##...#"
+        );
+        assert_eq!(life.alive(), 17);
+    }
+
+    #[test]
+    fn glider_wraps_around_torus() {
+        let glider = parse(".#....\n..#...\n###...\n......\n......\n......");
+        let mut life = Automaton::new(glider.clone(), Rule::CONWAY).boundary(Boundary::Toroidal);
+        assert_eq!(
+            life.find_cycle(100),
+            Some(Cycle {
+                start: 0,
+                period: 24
+            })
+        );
+        assert_eq!(life.cells(), &glider);
+        assert_eq!(life.generation(), 24);
+
+        let mut bounded = Automaton::new(glider, Rule::CONWAY);
+        assert_eq!(
+            bounded.find_cycle(100),
+            Some(Cycle {
+                start: 15,
+                period: 1
+            })
+        );
+        assert_eq!(bounded.alive(), 4);
+    }
+
+    #[test]
+    fn no_cycle_within_limit() {
+        let glider = parse(".#....\n..#...\n###...\n......\n......\n......");
+        let mut life = Automaton::new(glider, Rule::CONWAY).boundary(Boundary::Toroidal);
+        assert_eq!(life.find_cycle(23), None);
+        assert_eq!(life.generation(), 23);
+    }
+
+    #[test]
+    fn edges_wrap_only_on_torus() {
+        let edge = parse("#....\n#....\n#....");
+        let mut bounded = Automaton::new(edge.clone(), Rule::CONWAY);
+        bounded.step();
+        assert_eq!(bounded.render(), "Generation 1:\n.....\n##...\n.....");
+
+        let mut torus = Automaton::new(edge, Rule::CONWAY).boundary(Boundary::Toroidal);
+        torus.step();
+        // The column wraps into a ring, and the columns on either side of it
+        // see all three of its cells.
+        assert_eq!(torus.render(), "Generation 1:\n##..#\n##..#\n##..#");
+    }
+
+    #[rstest]
+    #[case(Boundary::Bounded)]
+    #[case(Boundary::Toroidal)]
+    #[case(Boundary::corners(53, 41))]
+    fn parallel_matches_sequential(#[case] boundary: Boundary) {
+        let mut state = 0x2545_f491_4f6c_dd1d_u64;
+        let cells = Grid::from_fn(53, 41, |_, _| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state.is_multiple_of(3)
+        });
+        let highlife = "B36/S23".parse().unwrap();
+        let mut sequential = Automaton::new(cells, highlife).boundary(boundary);
+        let mut parallel = sequential.clone().parallel(true);
+        for _ in 0..30 {
+            sequential.step();
+            parallel.step();
+            assert_eq!(parallel.cells(), sequential.cells());
+        }
+    }
+}
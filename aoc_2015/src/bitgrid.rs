@@ -0,0 +1,252 @@
+use std::ops::Range;
+
+const BITS: usize = u64::BITS as usize;
+
+/// A rectangular grid of booleans packed 64 to a word, with every row starting
+/// on a new word.
+///
+/// Rectangle updates work a word at a time: whole words in the middle of a row
+/// are replaced outright, and the partial words at its ends are masked.
+///
+/// # Examples
+///
+/// ```rust
+/// use aoc_2015::bitgrid::BitGrid;
+///
+/// let mut grid = BitGrid::new(100, 100);
+/// grid.set_rect(10, 10, 80, 2, true);
+/// grid.toggle_rect(0, 10, 20, 1);
+///
+/// assert_eq!(grid.count_ones(), 80 * 2 - 10 + 10);
+/// assert!(grid.get(5, 10));
+/// assert!(!grid.get(15, 10));
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub struct BitGrid {
+    words: Vec<u64>,
+    width: usize,
+    height: usize,
+    words_per_row: usize,
+}
+
+impl BitGrid {
+    /// A grid of `width` by `height` cleared cells.
+    pub fn new(width: usize, height: usize) -> Self {
+        let words_per_row = width.div_ceil(BITS);
+        Self {
+            words: vec![0; words_per_row * height],
+            width,
+            height,
+            words_per_row,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// The bytes the cells take, rounded up to whole words per row.
+    pub fn size_in_bytes(&self) -> usize {
+        std::mem::size_of_val(self.words.as_slice())
+    }
+
+    fn locate(&self, x: usize, y: usize) -> (usize, u64) {
+        assert!(
+            x < self.width && y < self.height,
+            "({}, {}) out of the {}x{} grid",
+            x,
+            y,
+            self.width,
+            self.height
+        );
+        (y * self.words_per_row + x / BITS, 1 << (x % BITS))
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> bool {
+        let (word, bit) = self.locate(x, y);
+        self.words[word] & bit != 0
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, value: bool) {
+        let (word, bit) = self.locate(x, y);
+        if value {
+            self.words[word] |= bit;
+        } else {
+            self.words[word] &= !bit;
+        }
+    }
+
+    /// Sets every cell of the `width` by `height` rectangle whose top-left
+    /// corner is at (`x`, `y`) to `value`.
+    ///
+    /// # Panics
+    ///
+    /// If the rectangle goes out of the grid.
+    pub fn set_rect(&mut self, x: usize, y: usize, width: usize, height: usize, value: bool) {
+        if value {
+            self.update_rect(x, y, width, height, |word, mask| word | mask);
+        } else {
+            self.update_rect(x, y, width, height, |word, mask| word & !mask);
+        }
+    }
+
+    /// Flips every cell of the rectangle, as in [`BitGrid::set_rect`].
+    pub fn toggle_rect(&mut self, x: usize, y: usize, width: usize, height: usize) {
+        self.update_rect(x, y, width, height, |word, mask| word ^ mask);
+    }
+
+    /// Replaces each word the rectangle overlaps with `update(word, mask)`,
+    /// where `mask` has the bits of the word inside the rectangle set.
+    fn update_rect(
+        &mut self,
+        x: usize,
+        y: usize,
+        width: usize,
+        height: usize,
+        update: impl Fn(u64, u64) -> u64,
+    ) {
+        assert!(
+            x + width <= self.width && y + height <= self.height,
+            "rectangle {}x{} at ({}, {}) out of the {}x{} grid",
+            width,
+            height,
+            x,
+            y,
+            self.width,
+            self.height
+        );
+        if width == 0 {
+            return;
+        }
+
+        let masks = row_masks(x..x + width);
+        for row in y..y + height {
+            let start = row * self.words_per_row + x / BITS;
+            for (word, &mask) in self.words[start..start + masks.len()]
+                .iter_mut()
+                .zip(&masks)
+            {
+                *word = update(*word, mask);
+            }
+        }
+    }
+
+    /// The number of set cells.
+    pub fn count_ones(&self) -> usize {
+        self.words
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+}
+
+/// The masks of the words spanned by the cells `columns` of a row, from the
+/// word holding the first cell.
+fn row_masks(columns: Range<usize>) -> Vec<u64> {
+    let first = columns.start / BITS;
+    let last = (columns.end - 1) / BITS;
+    (first..=last)
+        .map(|word| {
+            let low = columns.start.max(word * BITS) - word * BITS;
+            let high = columns.end.min((word + 1) * BITS) - word * BITS;
+            let below_high = if high == BITS {
+                u64::MAX
+            } else {
+                (1 << high) - 1
+            };
+            below_high & !((1 << low) - 1)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case(0..64, vec![u64::MAX])]
+    #[case(0..1, vec![1])]
+    #[case(3..5, vec![0b11000])]
+    #[case(60..68, vec![0xF << 60, 0xF])]
+    #[case(63..129, vec![1 << 63, u64::MAX, 1])]
+    fn masks(#[case] columns: Range<usize>, #[case] expected: Vec<u64>) {
+        assert_eq!(row_masks(columns), expected);
+    }
+
+    /// Compares with a plain grid of booleans on rectangles straddling words.
+    #[test]
+    fn matches_cell_by_cell() {
+        let (width, height) = (150, 7);
+        let mut bits = BitGrid::new(width, height);
+        let mut cells = vec![vec![false; width]; height];
+
+        let rects = [
+            (0, 0, 150, 7, 0),
+            (3, 1, 61, 3, 1),
+            (63, 0, 2, 7, 2),
+            (64, 2, 64, 4, 2),
+            (10, 0, 130, 1, 1),
+            (149, 6, 1, 1, 2),
+            (20, 3, 0, 3, 2),
+        ];
+        for (x, y, w, h, op) in rects {
+            match op {
+                0 => bits.set_rect(x, y, w, h, true),
+                1 => bits.set_rect(x, y, w, h, false),
+                _ => bits.toggle_rect(x, y, w, h),
+            }
+            for row in &mut cells[y..y + h] {
+                for cell in &mut row[x..x + w] {
+                    *cell = match op {
+                        0 => true,
+                        1 => false,
+                        _ => !*cell,
+                    };
+                }
+            }
+
+            for (y, row) in cells.iter().enumerate() {
+                for (x, &cell) in row.iter().enumerate() {
+                    assert_eq!(bits.get(x, y), cell, "at ({}, {})", x, y);
+                }
+            }
+            assert_eq!(
+                bits.count_ones(),
+                cells.iter().flatten().filter(|&&cell| cell).count()
+            );
+        }
+    }
+
+    #[test]
+    fn set_single_cells() {
+        let mut grid = BitGrid::new(65, 2);
+        grid.set(64, 1, true);
+        grid.set(0, 0, true);
+        grid.set(0, 0, false);
+
+        assert!(grid.get(64, 1));
+        assert!(!grid.get(0, 0));
+        assert_eq!(grid.count_ones(), 1);
+        assert_eq!((grid.width(), grid.height()), (65, 2));
+        assert_eq!(grid.size_in_bytes(), 2 * 2 * 8);
+    }
+
+    #[test]
+    #[should_panic(expected = "rectangle 2x1 at (64, 0) out of the 65x2 grid")]
+    fn rect_out_of_bounds() {
+        BitGrid::new(65, 2).toggle_rect(64, 0, 2, 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "(65, 0) out of the 65x2 grid")]
+    fn cell_out_of_bounds() {
+        BitGrid::new(65, 2).get(65, 0);
+    }
+}
@@ -0,0 +1,375 @@
+use std::{
+    fs,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use crate::{
+    error::{Error, Result},
+    registry::{Outcome, RunError, Solution},
+};
+
+/// What a cached result depends on: the solution, and the exact input and
+/// sources it was computed from.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct CacheKey {
+    pub day: u8,
+    pub part: u8,
+    pub variant: Option<String>,
+    pub input_hash: String,
+    /// The hash of the `dayN` module, of the shared modules it runs, and of
+    /// the data files it reads.
+    pub source_hash: String,
+}
+
+impl CacheKey {
+    /// The key of `solution` on `input`, reading its data files as they are
+    /// now. A missing file hashes as empty: the solution fails without it
+    /// anyway, and failures are not cached.
+    pub fn new(solution: &Solution, input: &str) -> Self {
+        let files: Vec<_> = solution
+            .data_files()
+            .iter()
+            .map(|path| fs::read_to_string(path).unwrap_or_default())
+            .collect();
+        Self::with_files(solution, input, &files)
+    }
+
+    fn with_files(solution: &Solution, input: &str, files: &[String]) -> Self {
+        let mut contents: Vec<&str> = solution.sources().collect();
+        contents.extend(files.iter().map(String::as_str));
+        Self {
+            day: solution.day,
+            part: solution.part,
+            variant: solution.variant.map(str::to_lowercase),
+            input_hash: hash(input),
+            source_hash: hash_all(contents),
+        }
+    }
+
+    /// Entries of the same solution share this prefix, whatever their hashes.
+    fn prefix(&self) -> String {
+        format!(
+            "part{}-{}",
+            self.part,
+            self.variant.as_deref().unwrap_or("default")
+        )
+    }
+
+    fn file_name(&self) -> String {
+        format!("{}-{}-{}", self.prefix(), self.source_hash, self.input_hash)
+    }
+}
+
+fn hash(content: &str) -> String {
+    format!("{:x}", md5::compute(content))
+}
+
+/// The hash of several contents, each ending with a NUL so that moving code
+/// from one to the next changes it.
+fn hash_all<'a>(contents: impl IntoIterator<Item = &'a str>) -> String {
+    let mut context = md5::Context::new();
+    for content in contents {
+        context.consume(content);
+        context.consume([0]);
+    }
+    format!("{:x}", context.compute())
+}
+
+/// A directory of solution results, one file per [`CacheKey`] under a
+/// directory per day.
+///
+/// Storing a result drops the entries of the same solution computed from an
+/// older source, so editing a day only invalidates that day's entries, and a
+/// changed input simply misses.
+pub struct Cache {
+    dir: PathBuf,
+}
+
+impl Cache {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// The cache used by the runner, under the crate's `.cache` directory.
+    pub fn default_dir() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join(".cache/results")
+    }
+
+    fn day_dir(&self, day: u8) -> PathBuf {
+        self.dir.join(format!("day{}", day))
+    }
+
+    /// Returns the cached outcome of `key`, if any. Unreadable entries are
+    /// treated as missing.
+    pub fn get(&self, key: &CacheKey) -> Option<Outcome> {
+        let content = fs::read_to_string(self.day_dir(key.day).join(key.file_name())).ok()?;
+        let mut parts = content.splitn(3, '\n');
+        let generator = parts.next()?.parse().ok()?;
+        let runner = parts.next()?.parse().ok()?;
+        let answer = parts.next()?.to_string();
+
+        Some(Outcome {
+            answer,
+            generator: Duration::from_nanos(generator),
+            runner: Duration::from_nanos(runner),
+        })
+    }
+
+    pub fn put(&self, key: &CacheKey, outcome: &Outcome) -> Result<()> {
+        let dir = self.day_dir(key.day);
+        fs::create_dir_all(&dir).map_err(|source| Error::Io {
+            path: dir.clone(),
+            source,
+        })?;
+
+        self.evict_stale(key)?;
+
+        let path = dir.join(key.file_name());
+        let content = format!(
+            "{}\n{}\n{}",
+            outcome.generator.as_nanos(),
+            outcome.runner.as_nanos(),
+            outcome.answer
+        );
+        fs::write(&path, content).map_err(|source| Error::Io { path, source })
+    }
+
+    /// Removes the entries of the same solution built from another source.
+    fn evict_stale(&self, key: &CacheKey) -> Result<()> {
+        let dir = self.day_dir(key.day);
+        let prefix = format!("{}-", key.prefix());
+        let current = format!("{}{}-", prefix, key.source_hash);
+
+        let entries = fs::read_dir(&dir).map_err(|source| Error::Io {
+            path: dir.clone(),
+            source,
+        })?;
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if name.starts_with(&prefix) && !name.starts_with(&current) {
+                match fs::remove_file(entry.path()) {
+                    Err(e) if e.kind() != ErrorKind::NotFound => {
+                        return Err(Error::Io {
+                            path: entry.path(),
+                            source: e,
+                        })
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the cached outcome of `solution` on `input`, running it and
+    /// caching its result on a miss. The flag tells whether it was a hit.
+    pub fn run(
+        &self,
+        solution: &Solution,
+        input: &str,
+    ) -> std::result::Result<(Outcome, bool), RunError> {
+        let key = CacheKey::new(solution, input);
+        if let Some(outcome) = self.get(&key) {
+            return Ok((outcome, true));
+        }
+
+        let outcome = solution.run(input)?;
+        if let Err(e) = self.put(&key, &outcome) {
+            eprintln!("Could not cache {}: {}", solution, e);
+        }
+        Ok((outcome, false))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(name: &str) -> Self {
+            let dir =
+                env::temp_dir().join(format!("aoc_2015-cache-{}-{}", name, std::process::id()));
+            let _ = fs::remove_dir_all(&dir);
+            Self(dir)
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn key(day: u8, part: u8, input: &str, source: &str) -> CacheKey {
+        CacheKey {
+            day,
+            part,
+            variant: None,
+            input_hash: hash(input),
+            source_hash: hash(source),
+        }
+    }
+
+    fn outcome(answer: &str) -> Outcome {
+        Outcome {
+            answer: answer.to_string(),
+            generator: Duration::from_nanos(12),
+            runner: Duration::from_micros(34),
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let dir = TempDir::new("round-trip");
+        let cache = Cache::new(&dir.0);
+        let key = key(4, 2, "abcdef", "fn solve_two() {}");
+
+        assert_eq!(cache.get(&key), None);
+
+        cache.put(&key, &outcome("multi\nline")).unwrap();
+        assert_eq!(cache.get(&key), Some(outcome("multi\nline")));
+    }
+
+    #[test]
+    fn changed_input_misses() {
+        let dir = TempDir::new("input");
+        let cache = Cache::new(&dir.0);
+        cache
+            .put(&key(4, 2, "abcdef", "v1"), &outcome("1"))
+            .unwrap();
+
+        assert_eq!(cache.get(&key(4, 2, "pqrstuv", "v1")), None);
+        assert_eq!(cache.get(&key(4, 2, "abcdef", "v1")), Some(outcome("1")));
+    }
+
+    #[test]
+    fn changed_source_evicts_only_that_solution() {
+        let dir = TempDir::new("source");
+        let cache = Cache::new(&dir.0);
+        cache
+            .put(&key(4, 1, "abcdef", "v1"), &outcome("1"))
+            .unwrap();
+        cache
+            .put(&key(4, 2, "abcdef", "v1"), &outcome("2"))
+            .unwrap();
+        cache.put(&key(10, 2, "1", "v1"), &outcome("3")).unwrap();
+
+        cache
+            .put(&key(4, 2, "abcdef", "v2"), &outcome("4"))
+            .unwrap();
+
+        assert_eq!(cache.get(&key(4, 2, "abcdef", "v1")), None);
+        assert_eq!(cache.get(&key(4, 2, "abcdef", "v2")), Some(outcome("4")));
+        assert_eq!(cache.get(&key(4, 1, "abcdef", "v1")), Some(outcome("1")));
+        assert_eq!(cache.get(&key(10, 2, "1", "v1")), Some(outcome("3")));
+    }
+
+    #[test]
+    fn variants_are_separate() {
+        let dir = TempDir::new("variant");
+        let cache = Cache::new(&dir.0);
+        let default = key(5, 1, "abc", "v1");
+        let clearer = CacheKey {
+            variant: Some("clearer".to_string()),
+            ..default.clone()
+        };
+
+        cache.put(&default, &outcome("1")).unwrap();
+        cache.put(&clearer, &outcome("2")).unwrap();
+
+        assert_eq!(cache.get(&default), Some(outcome("1")));
+        assert_eq!(cache.get(&clearer), Some(outcome("2")));
+    }
+
+    #[test]
+    fn source_hash_follows_module() {
+        let day4 = crate::registry::solutions_of(4).next().unwrap();
+        let day5 = crate::registry::solutions_of(5).next().unwrap();
+
+        assert_eq!(
+            CacheKey::new(day4, "x").source_hash,
+            hash_all([include_str!("day4.rs"), include_str!("miner.rs")])
+        );
+        assert_ne!(
+            CacheKey::new(day4, "x").source_hash,
+            CacheKey::new(day5, "x").source_hash
+        );
+    }
+
+    #[test]
+    fn day10_key_follows_run_length_encoding() {
+        let day10 = crate::registry::solutions_of(10).last().unwrap();
+        assert_eq!(
+            CacheKey::new(day10, "1").source_hash,
+            hash_all([
+                include_str!("day10.rs"),
+                include_str!("iter.rs"),
+                include_str!("rle.rs"),
+            ])
+        );
+    }
+
+    #[test]
+    fn changed_dependency_evicts() {
+        let dir = TempDir::new("dependency");
+        let cache = Cache::new(&dir.0);
+        let key = |miner: &str| CacheKey {
+            source_hash: hash_all([include_str!("day4.rs"), miner]),
+            ..key(4, 2, "abcdef", "")
+        };
+        cache.put(&key("v1"), &outcome("1")).unwrap();
+        assert_eq!(cache.get(&key("v1")), Some(outcome("1")));
+
+        cache.put(&key("v2"), &outcome("2")).unwrap();
+        assert_eq!(cache.get(&key("v1")), None);
+        assert_eq!(cache.get(&key("v2")), Some(outcome("2")));
+    }
+
+    #[test]
+    fn changed_rule_file_evicts() {
+        let dir = TempDir::new("rules");
+        let cache = Cache::new(&dir.0);
+        let day5 = crate::registry::solutions_of(5).next().unwrap();
+        let key = |part1: &str| {
+            let files = [part1.to_string(), "all(run(2))".to_string()];
+            CacheKey::with_files(day5, "aaa", &files)
+        };
+        cache.put(&key("all(run(2))"), &outcome("1")).unwrap();
+        assert_eq!(cache.get(&key("all(run(2))")), Some(outcome("1")));
+
+        assert_eq!(cache.get(&key("all(run(3))")), None);
+        cache.put(&key("all(run(3))"), &outcome("0")).unwrap();
+        assert_eq!(cache.get(&key("all(run(2))")), None);
+    }
+
+    #[test]
+    fn key_reads_the_rule_files() {
+        let day5 = crate::registry::solutions_of(5).next().unwrap();
+        let files: Vec<_> = ["day5-part1.rules", "day5-part2.rules"]
+            .iter()
+            .map(|file| fs::read_to_string(crate::rules::rules_dir().join(file)).unwrap())
+            .collect();
+        assert_eq!(
+            CacheKey::new(day5, "x"),
+            CacheKey::with_files(day5, "x", &files)
+        );
+        assert_ne!(
+            CacheKey::new(day5, "x"),
+            CacheKey::with_files(day5, "x", &[])
+        );
+    }
+
+    #[test]
+    fn contents_do_not_run_together() {
+        assert_ne!(hash_all(["ab", "c"]), hash_all(["a", "bc"]));
+    }
+}
@@ -0,0 +1,335 @@
+use std::{fmt::Write, time::Duration};
+
+use crate::{
+    error::Result,
+    input::{self, DEFAULT_INPUT},
+    registry::{self, Solution},
+    DAYS,
+};
+
+/// How far along the solutions of a day are.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum DayStatus {
+    /// Both parts are solved.
+    Complete,
+    /// A part is missing or only returns a hard-coded answer.
+    Partial,
+    /// There is no `dayN` module.
+    Missing,
+}
+
+impl DayStatus {
+    fn of(solutions: &[&Solution]) -> Self {
+        let solved = |part| solutions.iter().any(|s| s.part == part && !s.placeholder);
+
+        if solutions.is_empty() {
+            Self::Missing
+        } else if solved(1) && solved(2) {
+            Self::Complete
+        } else {
+            Self::Partial
+        }
+    }
+
+    fn symbol(self) -> char {
+        match self {
+            Self::Complete => '+',
+            Self::Partial => '~',
+            Self::Missing => '.',
+        }
+    }
+}
+
+/// A color band for the median runtime of a day.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Band {
+    Fast,
+    Moderate,
+    Slow,
+    Sluggish,
+}
+
+impl Band {
+    pub fn of(runtime: Duration) -> Self {
+        match runtime.as_millis() {
+            0..10 => Self::Fast,
+            10..100 => Self::Moderate,
+            100..1000 => Self::Slow,
+            _ => Self::Sluggish,
+        }
+    }
+
+    fn ansi(self) -> &'static str {
+        match self {
+            Self::Fast => "\x1b[42;30m",
+            Self::Moderate => "\x1b[43;30m",
+            Self::Slow => "\x1b[41;30m",
+            Self::Sluggish => "\x1b[45;30m",
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Fast => "< 10ms",
+            Self::Moderate => "< 100ms",
+            Self::Slow => "< 1s",
+            Self::Sluggish => ">= 1s",
+        }
+    }
+}
+
+const RESET: &str = "\x1b[0m";
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct CalendarDay {
+    pub day: u8,
+    /// Stars with a recorded answer for the default input.
+    pub stars: u8,
+    pub status: DayStatus,
+    /// The median runtime of both parts on the default input, if measured.
+    pub median: Option<Duration>,
+}
+
+/// The status overview of a whole year.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Calendar {
+    pub year: u32,
+    pub days: Vec<CalendarDay>,
+}
+
+impl Calendar {
+    /// Builds the calendar from the solution registry and the answers of the
+    /// default inputs, without measuring runtimes.
+    pub fn load(year: u32) -> Result<Self> {
+        let mut days = Vec::new();
+        for day in 1..=DAYS {
+            let solutions: Vec<_> = registry::solutions_of(day).collect();
+            let stars = match default_input(day)? {
+                Some(input) => (1..=2).filter(|&p| input.answers.get(p).is_some()).count() as u8,
+                None => 0,
+            };
+
+            days.push(CalendarDay {
+                day,
+                stars,
+                status: DayStatus::of(&solutions),
+                median: None,
+            });
+        }
+
+        Ok(Self { year, days })
+    }
+
+    /// Runs the solution of each part of every day `runs` times on its default
+    /// input and records the median of the total runtime. Variants are left
+    /// out, see [`timed_solutions`]. Days that fail are skipped.
+    pub fn measure(&mut self, runs: usize) -> Result<()> {
+        for day in &mut self.days {
+            let Some(input) = default_input(day.day)? else {
+                continue;
+            };
+
+            let solutions = timed_solutions(day.day);
+            if solutions.is_empty() {
+                continue;
+            }
+
+            let mut totals = Vec::with_capacity(runs);
+            for _ in 0..runs {
+                let total: Option<Duration> = solutions
+                    .iter()
+                    .map(|s| s.run(&input.text).ok().map(|o| o.total()))
+                    .sum();
+                totals.extend(total);
+            }
+
+            day.median = median(&mut totals);
+        }
+
+        Ok(())
+    }
+
+    /// Renders the calendar as a week grid, each day showing its stars and
+    /// status, with its median runtime as the background color.
+    pub fn render(&self, color: bool) -> String {
+        let mut out = String::new();
+        writeln!(out, "Advent of code {}", self.year).unwrap();
+        writeln!(out, "  Mon    Tue    Wed    Thu    Fri    Sat    Sun").unwrap();
+
+        let offset = weekday_of_december_first(self.year);
+        let mut column = 0;
+        out.push_str(&"       ".repeat(offset));
+        column += offset;
+
+        for day in &self.days {
+            let cell = format!(
+                "{:>2} {:<2}{}",
+                day.day,
+                "*".repeat(day.stars as usize),
+                day.status.symbol()
+            );
+            match day.median.map(Band::of) {
+                Some(band) if color => write!(out, "{}{}{}", band.ansi(), cell, RESET).unwrap(),
+                _ => out.push_str(&cell),
+            }
+
+            column += 1;
+            if column % 7 == 0 {
+                out.push('\n');
+            } else {
+                out.push(' ');
+            }
+        }
+
+        let mut out = format!("{}\n\n", out.trim_end());
+        let stars: u32 = self.days.iter().map(|d| d.stars as u32).sum();
+        writeln!(out, "{} stars   + complete   ~ partial   . missing", stars).unwrap();
+
+        if color {
+            let legend: Vec<_> = [Band::Fast, Band::Moderate, Band::Slow, Band::Sluggish]
+                .into_iter()
+                .map(|band| format!("{} {} {}", band.ansi(), band.label(), RESET))
+                .collect();
+            writeln!(out, "median runtime: {}", legend.join(" ")).unwrap();
+        }
+
+        out
+    }
+}
+
+/// The solution timed for each part of `day`: the one without a variant, or
+/// the first variant when the part has no default, as day 5 part 1.
+fn timed_solutions(day: u8) -> Vec<&'static Solution> {
+    (1..=2)
+        .filter_map(|part| {
+            let solutions: Vec<_> = registry::solutions_of(day)
+                .filter(|s| s.part == part)
+                .collect();
+            solutions
+                .iter()
+                .find(|s| s.variant.is_none())
+                .or(solutions.first())
+                .copied()
+        })
+        .collect()
+}
+
+fn default_input(day: u8) -> Result<Option<input::NamedInput>> {
+    Ok(input::inputs(&format!("day{}", day))?
+        .into_iter()
+        .find(|input| input.name == DEFAULT_INPUT))
+}
+
+fn median(values: &mut [Duration]) -> Option<Duration> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort();
+    Some(values[values.len() / 2])
+}
+
+/// The weekday of the first of December, `0` being Monday.
+fn weekday_of_december_first(year: u32) -> usize {
+    // Sakamoto's method, where `0` is Sunday. December needs no year shift.
+    let y = year as usize;
+    let sunday_based = (y + y / 4 - y / 100 + y / 400 + 4 + 1) % 7;
+    (sunday_based + 6) % 7
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn calendar() -> Calendar {
+        let days = (1..=DAYS)
+            .map(|day| CalendarDay {
+                day,
+                stars: if day <= 2 { 2 } else { 0 },
+                status: match day {
+                    1..=2 => DayStatus::Complete,
+                    3 => DayStatus::Partial,
+                    _ => DayStatus::Missing,
+                },
+                median: (day == 1).then(|| Duration::from_millis(20)),
+            })
+            .collect();
+
+        Calendar { year: 2015, days }
+    }
+
+    #[test]
+    fn december_first() {
+        // Tuesday, Thursday and Sunday.
+        assert_eq!(weekday_of_december_first(2015), 1);
+        assert_eq!(weekday_of_december_first(2022), 3);
+        assert_eq!(weekday_of_december_first(2024), 6);
+    }
+
+    #[test]
+    fn status_of_solutions() {
+        let solutions: Vec<_> = registry::solutions_of(1).collect();
+        assert_eq!(DayStatus::of(&solutions), DayStatus::Complete);
+
+        let solutions: Vec<_> = registry::solutions_of(7).collect();
+        assert_eq!(DayStatus::of(&solutions), DayStatus::Partial);
+
+        let solutions: Vec<_> = registry::solutions_of(25).collect();
+        assert_eq!(DayStatus::of(&solutions), DayStatus::Missing);
+    }
+
+    #[test]
+    fn time_the_default_solutions() {
+        let variants = |day| -> Vec<_> {
+            timed_solutions(day)
+                .iter()
+                .map(|s| (s.part, s.variant))
+                .collect()
+        };
+        assert_eq!(variants(6), [(1, None), (2, None)]);
+        assert_eq!(variants(9), [(1, None), (2, None)]);
+        assert_eq!(variants(5), [(1, Some("Clearer")), (2, None)]);
+        assert_eq!(variants(25), []);
+    }
+
+    #[test]
+    fn bands() {
+        assert_eq!(Band::of(Duration::from_micros(300)), Band::Fast);
+        assert_eq!(Band::of(Duration::from_millis(10)), Band::Moderate);
+        assert_eq!(Band::of(Duration::from_millis(999)), Band::Slow);
+        assert_eq!(Band::of(Duration::from_secs(3)), Band::Sluggish);
+    }
+
+    #[test]
+    fn median_runtime() {
+        let mut values = [3, 1, 2].map(Duration::from_millis);
+        assert_eq!(median(&mut values), Some(Duration::from_millis(2)));
+        assert_eq!(median(&mut []), None);
+    }
+
+    #[test]
+    fn render_without_color() {
+        assert_eq!(
+            calendar().render(false),
+            "\
+Advent of code 2015
+  Mon    Tue    Wed    Thu    Fri    Sat    Sun
+        1 **+  2 **+  3   ~  4   .  5   .  6   .
+ 7   .  8   .  9   . 10   . 11   . 12   . 13   .
+14   . 15   . 16   . 17   . 18   . 19   . 20   .
+21   . 22   . 23   . 24   . 25   .
+
+4 stars   + complete   ~ partial   . missing
+"
+        );
+    }
+
+    #[test]
+    fn render_with_color() {
+        let rendered = calendar().render(true);
+
+        assert!(rendered.contains("\x1b[43;30m 1 **+\x1b[0m"));
+        assert!(rendered.contains("median runtime:"));
+    }
+}
@@ -0,0 +1,383 @@
+//! Quoted string literals: their escape sequences, decoded to the bytes they
+//! stand for, and bytes encoded back into literals.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use aoc_2015::codec::{decode, encode, Dialect, EscapeError};
+//!
+//! assert_eq!(decode(r#""a\"b\x27""#, Dialect::AoC).unwrap(), b"a\"b'");
+//! assert_eq!(encode(b"a\"b\n", Dialect::Json), br#""a\"b\n""#);
+//! assert_eq!(encode(b"\xff", Dialect::RustBytes), br#"b"\xff""#);
+//!
+//! assert_eq!(
+//!     decode(r#""\xzz""#, Dialect::AoC),
+//!     Err(EscapeError::InvalidHex { offset: 1 })
+//! );
+//! ```
+
+use derive_more::derive::{Display, Error};
+
+/// The escape rules of a kind of string literal.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Dialect {
+    /// The literals of day 8: `\\`, `\"` and `\x` with two hex digits. Any
+    /// other byte stands for itself.
+    AoC,
+    /// Rust byte strings, like `b"a\n"`: only ASCII, with `\n`, `\r`, `\t`,
+    /// `\0`, `\'` and two-digit `\x` escapes on top of those of day 8.
+    RustBytes,
+    /// JSON strings: control characters must be escaped, as `\b`, `\f`, `\n`,
+    /// `\r`, `\t` or `\u` with four hex digits, which decode to UTF-8.
+    Json,
+    /// C strings, with the escapes of `\a` to `\v`, `\?`, `\'`, octal escapes
+    /// of up to three digits, and `\x` escapes of any number of hex digits.
+    C,
+}
+
+impl Dialect {
+    /// What a literal starts with; they all end with a double quote.
+    pub fn opening(self) -> &'static str {
+        match self {
+            Dialect::RustBytes => "b\"",
+            Dialect::AoC | Dialect::Json | Dialect::C => "\"",
+        }
+    }
+}
+
+/// Why a literal could not be decoded, with the byte offset of the problem
+/// in the literal.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Display, Error)]
+pub enum EscapeError {
+    #[display("expected a literal between `{opening}` and `\"`")]
+    MissingQuotes {
+        #[error(not(source))]
+        opening: &'static str,
+    },
+    #[display("unescaped quote at byte {offset}")]
+    UnescapedQuote { offset: usize },
+    #[display("unescaped byte {byte:#04x} at byte {offset}")]
+    UnescapedByte { offset: usize, byte: u8 },
+    #[display("unfinished escape at byte {offset}")]
+    UnfinishedEscape { offset: usize },
+    #[display("unknown escape `\\{}` at byte {offset}", *escape as char)]
+    UnknownEscape { offset: usize, escape: u8 },
+    #[display("invalid hex digits at byte {offset}")]
+    InvalidHex { offset: usize },
+    #[display("escaped value out of range at byte {offset}")]
+    OutOfRange { offset: usize },
+    #[display("invalid code point at byte {offset}")]
+    InvalidCodePoint { offset: usize },
+}
+
+/// The bytes `literal` stands for, quotes included in `literal`.
+pub fn decode(literal: impl AsRef<[u8]>, dialect: Dialect) -> Result<Vec<u8>, EscapeError> {
+    let literal = literal.as_ref();
+    let opening = dialect.opening();
+    let body = literal
+        .strip_prefix(opening.as_bytes())
+        .and_then(|rest| rest.strip_suffix(b"\""))
+        .ok_or(EscapeError::MissingQuotes { opening })?;
+
+    let mut decoder = Decoder {
+        body,
+        start: opening.len(),
+        position: 0,
+        dialect,
+    };
+    let mut bytes = Vec::with_capacity(body.len());
+    while let Some(byte) = decoder.next() {
+        let offset = decoder.offset(decoder.position - 1);
+        match byte {
+            b'"' => return Err(EscapeError::UnescapedQuote { offset }),
+            b'\\' => decoder.escape(offset, &mut bytes)?,
+            byte if !decoder.allows_raw(byte) => {
+                return Err(EscapeError::UnescapedByte { offset, byte })
+            }
+            byte => bytes.push(byte),
+        }
+    }
+    Ok(bytes)
+}
+
+/// The literal standing for `bytes`, such that
+/// `decode(encode(bytes, dialect), dialect) == Ok(bytes)`.
+pub fn encode(bytes: &[u8], dialect: Dialect) -> Vec<u8> {
+    let mut literal = dialect.opening().as_bytes().to_vec();
+    for &byte in bytes {
+        match (dialect, byte) {
+            (_, b'"' | b'\\') => literal.extend([b'\\', byte]),
+            (Dialect::AoC, _) => literal.push(byte),
+            (_, b'\n') => literal.extend(b"\\n"),
+            (_, b'\r') => literal.extend(b"\\r"),
+            (_, b'\t') => literal.extend(b"\\t"),
+            (Dialect::Json | Dialect::C, 0x08) => literal.extend(b"\\b"),
+            (Dialect::Json | Dialect::C, 0x0c) => literal.extend(b"\\f"),
+            (Dialect::C, 0x07) => literal.extend(b"\\a"),
+            (Dialect::C, 0x0b) => literal.extend(b"\\v"),
+            (Dialect::Json, 0x00..=0x1f) => {
+                literal.extend(format!("\\u{:04x}", byte).bytes());
+            }
+            // Bytes over ASCII go through as is, as they are part of the
+            // UTF-8 of a character.
+            (Dialect::Json, _) => literal.push(byte),
+            (_, 0x20..=0x7e) => literal.push(byte),
+            (Dialect::RustBytes, 0) => literal.extend(b"\\0"),
+            (Dialect::RustBytes, _) => literal.extend(format!("\\x{:02x}", byte).bytes()),
+            // Always three octal digits, so that a digit after it is not read
+            // as part of it.
+            (Dialect::C, _) => literal.extend(format!("\\{:03o}", byte).bytes()),
+        }
+    }
+    literal.push(b'"');
+    literal
+}
+
+/// Reads the body of a literal, between its quotes.
+struct Decoder<'a> {
+    body: &'a [u8],
+    /// The offset of the body in the literal, for errors.
+    start: usize,
+    position: usize,
+    dialect: Dialect,
+}
+
+impl Decoder<'_> {
+    fn next(&mut self) -> Option<u8> {
+        let byte = *self.body.get(self.position)?;
+        self.position += 1;
+        Some(byte)
+    }
+
+    fn offset(&self, position: usize) -> usize {
+        self.start + position
+    }
+
+    fn allows_raw(&self, byte: u8) -> bool {
+        match self.dialect {
+            Dialect::AoC | Dialect::C => true,
+            Dialect::RustBytes => byte.is_ascii(),
+            Dialect::Json => byte >= 0x20,
+        }
+    }
+
+    /// Decodes the escape whose backslash is at `offset`.
+    fn escape(&mut self, offset: usize, bytes: &mut Vec<u8>) -> Result<(), EscapeError> {
+        let escape = self
+            .next()
+            .ok_or(EscapeError::UnfinishedEscape { offset })?;
+        let simple = match (self.dialect, escape) {
+            (_, b'\\' | b'"') => Some(escape),
+            (Dialect::RustBytes | Dialect::C, b'\'') => Some(escape),
+            (Dialect::RustBytes | Dialect::Json | Dialect::C, b'n') => Some(b'\n'),
+            (Dialect::RustBytes | Dialect::Json | Dialect::C, b'r') => Some(b'\r'),
+            (Dialect::RustBytes | Dialect::Json | Dialect::C, b't') => Some(b'\t'),
+            (Dialect::Json | Dialect::C, b'b') => Some(0x08),
+            (Dialect::Json | Dialect::C, b'f') => Some(0x0c),
+            (Dialect::RustBytes, b'0') => Some(0),
+            (Dialect::Json, b'/') => Some(b'/'),
+            (Dialect::C, b'a') => Some(0x07),
+            (Dialect::C, b'v') => Some(0x0b),
+            (Dialect::C, b'?') => Some(b'?'),
+            _ => None,
+        };
+        if let Some(byte) = simple {
+            bytes.push(byte);
+            return Ok(());
+        }
+
+        match (self.dialect, escape) {
+            (Dialect::AoC | Dialect::RustBytes, b'x') => {
+                bytes.push(self.hex(offset, 2, 2)? as u8);
+            }
+            (Dialect::C, b'x') => {
+                let value = self.hex(offset, 1, usize::MAX)?;
+                bytes.push(u8::try_from(value).map_err(|_| EscapeError::OutOfRange { offset })?);
+            }
+            (Dialect::C, b'0'..=b'7') => {
+                self.position -= 1;
+                let value = self.digits(8, 1, 3).unwrap_or(0);
+                bytes.push(u8::try_from(value).map_err(|_| EscapeError::OutOfRange { offset })?);
+            }
+            (Dialect::Json, b'u') => {
+                let c = self.code_point(offset)?;
+                bytes.extend(c.encode_utf8(&mut [0; 4]).as_bytes());
+            }
+            (_, escape) => return Err(EscapeError::UnknownEscape { offset, escape }),
+        }
+        Ok(())
+    }
+
+    /// Reads between `min` and `max` hex digits, as many as there are.
+    fn hex(&mut self, offset: usize, min: usize, max: usize) -> Result<u32, EscapeError> {
+        self.digits(16, min, max)
+            .ok_or(EscapeError::InvalidHex { offset })
+    }
+
+    /// Reads between `min` and `max` digits in `radix`, or `None` if there are
+    /// fewer than `min` or their value does not fit a `u32`.
+    fn digits(&mut self, radix: u32, min: usize, max: usize) -> Option<u32> {
+        let mut value = 0u32;
+        let mut count = 0;
+        while count < max {
+            let Some(digit) = self
+                .body
+                .get(self.position)
+                .and_then(|&b| (b as char).to_digit(radix))
+            else {
+                break;
+            };
+            value = value.checked_mul(radix)?.checked_add(digit)?;
+            self.position += 1;
+            count += 1;
+        }
+        (count >= min).then_some(value)
+    }
+
+    /// Reads the four hex digits of a `\u` escape, and those of a second one
+    /// after it if the first is the high half of a surrogate pair.
+    fn code_point(&mut self, offset: usize) -> Result<char, EscapeError> {
+        let invalid = EscapeError::InvalidCodePoint { offset };
+        let high = self.hex(offset, 4, 4)?;
+        let code = match high {
+            0xd800..=0xdbff => {
+                if self.body[self.position..].starts_with(b"\\u") {
+                    self.position += 2;
+                } else {
+                    return Err(invalid);
+                }
+                let low = self.hex(offset, 4, 4)?;
+                if !(0xdc00..=0xdfff).contains(&low) {
+                    return Err(invalid);
+                }
+                0x10000 + ((high - 0xd800) << 10) + (low - 0xdc00)
+            }
+            code => code,
+        };
+        char::from_u32(code).ok_or(invalid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::*;
+
+    const DIALECTS: [Dialect; 4] = [Dialect::AoC, Dialect::RustBytes, Dialect::Json, Dialect::C];
+
+    #[rstest]
+    #[case(Dialect::AoC, r#""\\\"\x41z""#, b"\\\"Az")]
+    #[case(Dialect::RustBytes, r#"b"\n\r\t\0\'\x7f""#, b"\n\r\t\0'\x7f")]
+    #[case(Dialect::Json, r#""\/\b\fAé""#, "/\x08\x0cAé".as_bytes())]
+    #[case(Dialect::Json, r#""😀""#, "😀".as_bytes())]
+    #[case(Dialect::Json, "\"é\"", "é".as_bytes())]
+    #[case(Dialect::C, r#""\a\v\?\101\0\x41\x041""#, b"\x07\x0b?A\0AA")]
+    #[case(Dialect::C, r#""\1234""#, b"S4")]
+    fn decode_escapes(#[case] dialect: Dialect, #[case] literal: &str, #[case] expected: &[u8]) {
+        assert_eq!(decode(literal, dialect), Ok(expected.to_vec()));
+    }
+
+    #[rstest]
+    #[case(Dialect::AoC, "abc", EscapeError::MissingQuotes { opening: "\"" })]
+    #[case(Dialect::AoC, "\"", EscapeError::MissingQuotes { opening: "\"" })]
+    #[case(Dialect::RustBytes, "\"a\"", EscapeError::MissingQuotes { opening: "b\"" })]
+    #[case(Dialect::AoC, r#""a"b""#, EscapeError::UnescapedQuote { offset: 2 })]
+    #[case(Dialect::AoC, r#""ab\""#, EscapeError::UnfinishedEscape { offset: 3 })]
+    #[case(Dialect::AoC, r#""\n""#, EscapeError::UnknownEscape { offset: 1, escape: b'n' })]
+    #[case(Dialect::AoC, r#""\x4""#, EscapeError::InvalidHex { offset: 1 })]
+    #[case(Dialect::AoC, r#""\x+1""#, EscapeError::InvalidHex { offset: 1 })]
+    #[case(Dialect::RustBytes, "b\"é\"", EscapeError::UnescapedByte { offset: 2, byte: 0xc3 })]
+    #[case(Dialect::Json, "\"\n\"", EscapeError::UnescapedByte { offset: 1, byte: b'\n' })]
+    #[case(Dialect::Json, r#""\ud83d""#, EscapeError::InvalidCodePoint { offset: 1 })]
+    #[case(Dialect::Json, r#""\ude00""#, EscapeError::InvalidCodePoint { offset: 1 })]
+    #[case(Dialect::Json, r#""\ud83dA""#, EscapeError::InvalidCodePoint { offset: 1 })]
+    #[case(Dialect::Json, r#""\x41""#, EscapeError::UnknownEscape { offset: 1, escape: b'x' })]
+    #[case(Dialect::C, r#""\400""#, EscapeError::OutOfRange { offset: 1 })]
+    #[case(Dialect::C, r#""\x100""#, EscapeError::OutOfRange { offset: 1 })]
+    #[case(Dialect::C, r#""\xg""#, EscapeError::InvalidHex { offset: 1 })]
+    fn decode_errors(
+        #[case] dialect: Dialect,
+        #[case] literal: &str,
+        #[case] expected: EscapeError,
+    ) {
+        assert_eq!(decode(literal, dialect), Err(expected));
+    }
+
+    #[test]
+    fn error_messages() {
+        assert_eq!(
+            EscapeError::UnknownEscape {
+                offset: 3,
+                escape: b'q'
+            }
+            .to_string(),
+            "unknown escape `\\q` at byte 3"
+        );
+        assert_eq!(
+            EscapeError::UnescapedByte {
+                offset: 1,
+                byte: b'\n'
+            }
+            .to_string(),
+            "unescaped byte 0x0a at byte 1"
+        );
+    }
+
+    #[rstest]
+    #[case(Dialect::AoC, b"\"\\\x01", b"\"\\\"\\\\\x01\"")]
+    #[case(Dialect::RustBytes, b"\0\x01\xff~", br#"b"\0\x01\xff~""#)]
+    #[case(Dialect::Json, b"\x01\x08\x7f", b"\"\\u0001\\b\x7f\"")]
+    #[case(Dialect::C, b"\x017\x07", br#""\0017\a""#)]
+    fn encode_escapes(#[case] dialect: Dialect, #[case] bytes: &[u8], #[case] expected: &[u8]) {
+        assert_eq!(encode(bytes, dialect), expected);
+    }
+
+    /// A xorshift generator, for reproducible byte strings.
+    fn random_bytes(seed: &mut u64, len: usize) -> Vec<u8> {
+        (0..len)
+            .map(|_| {
+                *seed ^= *seed << 13;
+                *seed ^= *seed >> 7;
+                *seed ^= *seed << 17;
+                (*seed >> 24) as u8
+            })
+            .collect()
+    }
+
+    #[test]
+    fn roundtrip_short_strings() {
+        let strings = std::iter::once(Vec::new())
+            .chain((0..=255).map(|a| vec![a]))
+            .chain((0..=255).flat_map(|a| (0..=255).map(move |b| vec![a, b])));
+        for bytes in strings {
+            for dialect in DIALECTS {
+                let literal = encode(&bytes, dialect);
+                assert_eq!(
+                    decode(&literal, dialect),
+                    Ok(bytes.clone()),
+                    "{:?}",
+                    literal
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn roundtrip_random_strings() {
+        let mut seed = 0x2015_0008;
+        for len in 0..2000 {
+            let bytes = random_bytes(&mut seed, len % 64);
+            for dialect in DIALECTS {
+                let literal = encode(&bytes, dialect);
+                assert_eq!(
+                    decode(&literal, dialect),
+                    Ok(bytes.clone()),
+                    "{:?}",
+                    literal
+                );
+            }
+        }
+    }
+}
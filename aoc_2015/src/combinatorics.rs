@@ -0,0 +1,338 @@
+//! Bounded searches over compositions, subsets and partitions.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use aoc_2015::combinatorics::{compositions, count_minimal_subset_sums, count_subset_sums};
+//!
+//! assert_eq!(compositions(2, 2).collect::<Vec<_>>(), [[0, 2], [1, 1], [2, 0]]);
+//!
+//! let containers = [20, 15, 10, 5, 5];
+//! assert_eq!(count_subset_sums(&containers, 25), 4);
+//! assert_eq!(count_minimal_subset_sums(&containers, 25), Some((2, 3)));
+//! ```
+
+use itertools::Itertools;
+
+/// Every way to write `n` as an ordered sum of `k` non-negative parts, in
+/// lexicographic order, computed one at a time.
+pub fn compositions(n: u32, k: usize) -> Compositions {
+    let first = match k {
+        0 if n > 0 => None,
+        0 => Some(Vec::new()),
+        _ => {
+            let mut parts = vec![0; k];
+            parts[k - 1] = n;
+            Some(parts)
+        }
+    };
+    Compositions { next: first }
+}
+
+/// The iterator of [`compositions`].
+pub struct Compositions {
+    next: Option<Vec<u32>>,
+}
+
+impl Iterator for Compositions {
+    type Item = Vec<u32>;
+
+    fn next(&mut self) -> Option<Vec<u32>> {
+        let current = self.next.take()?;
+
+        // Increment the last part that still has something after it, and move
+        // everything after it to the end.
+        let mut successor = current.clone();
+        let mut rest = 0;
+        for i in (0..successor.len().saturating_sub(1)).rev() {
+            rest += successor[i + 1];
+            if rest > 0 {
+                successor[i] += 1;
+                successor[i + 1..].fill(0);
+                *successor.last_mut().unwrap() = rest - 1;
+                self.next = Some(successor);
+                break;
+            }
+        }
+
+        Some(current)
+    }
+}
+
+/// The number of subsets of `items` summing to `target`, by size: the entry
+/// at index `len` counts the subsets of `len` items. Equal items in different
+/// positions make different subsets.
+pub fn subset_sum_counts(items: &[u64], target: u64) -> Vec<u64> {
+    let target = target as usize;
+    // ways[len][sum]: the subsets of the items seen so far of `len` items
+    // summing to `sum`.
+    let mut ways = vec![vec![0u64; target + 1]; items.len() + 1];
+    ways[0][0] = 1;
+    for (seen, &item) in items.iter().enumerate() {
+        let item = item as usize;
+        if item > target {
+            continue;
+        }
+        for len in (1..=seen + 1).rev() {
+            for sum in (item..=target).rev() {
+                ways[len][sum] += ways[len - 1][sum - item];
+            }
+        }
+    }
+    ways.into_iter().map(|by_sum| by_sum[target]).collect()
+}
+
+/// The number of subsets of `items` summing to `target`.
+pub fn count_subset_sums(items: &[u64], target: u64) -> u64 {
+    subset_sum_counts(items, target).into_iter().sum()
+}
+
+/// The fewest items summing to `target`, and the number of subsets of that
+/// many items doing so, if there is any.
+pub fn count_minimal_subset_sums(items: &[u64], target: u64) -> Option<(usize, u64)> {
+    subset_sum_counts(items, target)
+        .into_iter()
+        .enumerate()
+        .find(|&(_, count)| count > 0)
+}
+
+/// Every subset of `items` summing to `target`, as increasing indices, in
+/// lexicographic order. Subsets are pruned as soon as they go over `target`.
+pub fn subsets_with_sum(items: &[u64], target: u64) -> SubsetsWithSum<'_> {
+    SubsetsWithSum {
+        items,
+        target,
+        chosen: Vec::new(),
+        sum: 0,
+        next: 0,
+        started: false,
+    }
+}
+
+/// The iterator of [`subsets_with_sum`].
+pub struct SubsetsWithSum<'a> {
+    items: &'a [u64],
+    target: u64,
+    chosen: Vec<usize>,
+    sum: u64,
+    /// The next index to try adding to `chosen`.
+    next: usize,
+    started: bool,
+}
+
+impl Iterator for SubsetsWithSum<'_> {
+    type Item = Vec<usize>;
+
+    fn next(&mut self) -> Option<Vec<usize>> {
+        if !self.started {
+            self.started = true;
+            if self.target == 0 {
+                return Some(Vec::new());
+            }
+        }
+
+        loop {
+            if let Some(&item) = self.items.get(self.next) {
+                let index = self.next;
+                self.next += 1;
+                if self.sum + item <= self.target {
+                    self.chosen.push(index);
+                    self.sum += item;
+                    if self.sum == self.target {
+                        return Some(self.chosen.clone());
+                    }
+                }
+            } else {
+                let last = self.chosen.pop()?;
+                self.sum -= self.items[last];
+                self.next = last + 1;
+            }
+        }
+    }
+}
+
+/// Splits `items` into `k` groups of equal sum, choosing the first group
+/// with the fewest items, then the smallest `objective` among those.
+///
+/// The other groups are any valid split of the remaining items. Returns
+/// `None` if the items cannot be split evenly.
+///
+/// ```rust
+/// use aoc_2015::combinatorics::balanced_partition;
+///
+/// let weights = [1, 2, 3, 4, 5, 7, 8, 9, 10, 11];
+/// let groups =
+///     balanced_partition(&weights, 3, |group| group.iter().product::<u64>()).unwrap();
+///
+/// assert_eq!(groups[0], [9, 11]);
+/// assert!(groups.iter().all(|group| group.iter().sum::<u64>() == 20));
+/// ```
+pub fn balanced_partition<O, F>(items: &[u64], k: usize, objective: F) -> Option<Vec<Vec<u64>>>
+where
+    O: Ord,
+    F: Fn(&[u64]) -> O,
+{
+    let total: u64 = items.iter().sum();
+    if k == 0 || !total.is_multiple_of(k as u64) {
+        return None;
+    }
+    let target = total / k as u64;
+
+    for len in 0..=items.len() {
+        let candidates = (0..items.len())
+            .combinations(len)
+            .filter(|indices| indices.iter().map(|&i| items[i]).sum::<u64>() == target)
+            .map(|indices| {
+                let group = indices.iter().map(|&i| items[i]).collect_vec();
+                (objective(&group), indices, group)
+            })
+            .sorted_by(|(a, ..), (b, ..)| a.cmp(b));
+
+        for (_, indices, group) in candidates {
+            let rest = (0..items.len())
+                .filter(|i| !indices.contains(i))
+                .map(|i| items[i])
+                .collect_vec();
+            if let Some(mut groups) = split(&rest, k - 1, target) {
+                groups.insert(0, group);
+                return Some(groups);
+            }
+        }
+    }
+    None
+}
+
+/// Splits `items`, which sum to `parts * target`, into `parts` groups summing
+/// to `target`. The group holding the first item is chosen first, so that
+/// the same split is not tried in every order.
+fn split(items: &[u64], parts: usize, target: u64) -> Option<Vec<Vec<u64>>> {
+    match (parts, items.split_first()) {
+        (0, _) => items.is_empty().then(Vec::new),
+        (1, _) => Some(vec![items.to_vec()]),
+        (_, None) => (target == 0).then(|| vec![Vec::new(); parts]),
+        (_, Some((&first, others))) => {
+            let remainder = target.checked_sub(first)?;
+            subsets_with_sum(others, remainder).find_map(|indices| {
+                let mut group = vec![first];
+                group.extend(indices.iter().map(|&i| others[i]));
+                let rest = (0..others.len())
+                    .filter(|i| !indices.contains(i))
+                    .map(|i| others[i])
+                    .collect_vec();
+                let mut groups = split(&rest, parts - 1, target)?;
+                groups.insert(0, group);
+                Some(groups)
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::*;
+
+    /// Every subset of `items`, as the indices of its items.
+    fn all_subsets(items: &[u64]) -> impl Iterator<Item = Vec<usize>> + '_ {
+        (0..1u32 << items.len()).map(|mask| {
+            (0..items.len())
+                .filter(|i| mask & (1 << i) != 0)
+                .collect_vec()
+        })
+    }
+
+    #[rstest]
+    #[case(0, 0, 1)]
+    #[case(3, 0, 0)]
+    #[case(0, 3, 1)]
+    #[case(5, 1, 1)]
+    #[case(4, 3, 15)]
+    #[case(100, 4, 176_851)]
+    fn number_of_compositions(#[case] n: u32, #[case] k: usize, #[case] expected: usize) {
+        assert_eq!(compositions(n, k).count(), expected);
+    }
+
+    #[test]
+    fn compositions_in_order() {
+        let all = compositions(3, 3).collect_vec();
+
+        assert!(all.iter().all(|parts| parts.iter().sum::<u32>() == 3));
+        assert!(all.windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!(all.first(), Some(&vec![0, 0, 3]));
+        assert_eq!(all.last(), Some(&vec![3, 0, 0]));
+    }
+
+    #[test]
+    fn subset_sums_match_brute_force() {
+        let items = [3, 1, 4, 1, 5, 9, 2, 6, 5, 3, 0];
+        for target in 0..=40 {
+            let mut by_len = vec![0; items.len() + 1];
+            for subset in all_subsets(&items) {
+                if subset.iter().map(|&i| items[i]).sum::<u64>() == target {
+                    by_len[subset.len()] += 1;
+                }
+            }
+
+            assert_eq!(
+                subset_sum_counts(&items, target),
+                by_len,
+                "target {}",
+                target
+            );
+        }
+    }
+
+    #[test]
+    fn subsets_with_sum_match_brute_force() {
+        let items = [3, 1, 4, 1, 5, 9, 2, 6, 0];
+        for target in 0..=32 {
+            let expected = all_subsets(&items)
+                .filter(|subset| subset.iter().map(|&i| items[i]).sum::<u64>() == target)
+                .sorted()
+                .collect_vec();
+
+            assert_eq!(
+                subsets_with_sum(&items, target).collect_vec(),
+                expected,
+                "target {}",
+                target
+            );
+        }
+    }
+
+    #[test]
+    fn no_minimal_subset() {
+        assert_eq!(count_minimal_subset_sums(&[2, 4], 3), None);
+        assert_eq!(count_minimal_subset_sums(&[], 0), Some((0, 1)));
+    }
+
+    #[rstest]
+    #[case(3, vec![9, 11], 99)]
+    #[case(4, vec![4, 11], 44)]
+    fn balanced_groups(#[case] k: usize, #[case] first: Vec<u64>, #[case] entanglement: u64) {
+        let weights = [1, 2, 3, 4, 5, 7, 8, 9, 10, 11];
+        let groups =
+            balanced_partition(&weights, k, |group| group.iter().product::<u64>()).unwrap();
+
+        assert_eq!(groups[0], first);
+        assert_eq!(groups[0].iter().product::<u64>(), entanglement);
+        assert_eq!(groups.len(), k);
+        assert_eq!(
+            groups.iter().flatten().sorted().copied().collect_vec(),
+            weights
+        );
+        let sums = groups.iter().map(|group| group.iter().sum::<u64>());
+        assert!(sums.clone().all_equal(), "{:?}", sums.collect_vec());
+    }
+
+    #[rstest]
+    #[case(&[1, 2], 2)]
+    // Only {2, 2} makes 4, leaving 3, 3 and 2.
+    #[case(&[3, 3, 2, 2, 2], 3)]
+    #[case(&[1, 1], 0)]
+    fn unbalanced(#[case] items: &[u64], #[case] k: usize) {
+        assert_eq!(balanced_partition(items, k, |group| group.len()), None);
+    }
+}
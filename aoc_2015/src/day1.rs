@@ -1,24 +1,20 @@
 use aoc_runner_derive::aoc;
 
+use crate::iter::IteratorExt;
+
 #[aoc(day1, part1)]
 fn solve_one(input: &str) -> i32 {
     input.chars().map(parse).sum()
 }
 
 #[aoc(day1, part2)]
-fn solve_two(input: &str) -> i32 {
+fn solve_two(input: &str) -> usize {
     input
         .chars()
         .map(parse)
-        .enumerate()
-        .fold_until(0, |acc, (i, c)| {
-            *acc += c;
-            if *acc < 0 {
-                return Until::Break(i as i32 + 1);
-            }
-            Until::Continue
-        })
-        .expect_break("Santa didn't reach the basement")
+        .position_of_cumulative(|&floor| floor < 0)
+        .expect("Santa didn't reach the basement")
+        + 1
 }
 
 fn parse(ch: char) -> i32 {
@@ -29,38 +25,6 @@ fn parse(ch: char) -> i32 {
     }
 }
 
-#[derive(Debug, PartialEq)]
-enum Until<T> {
-    Continue,
-    Break(T),
-}
-
-impl<T> Until<T> {
-    fn expect_break(self, message: &str) -> T {
-        match self {
-            Until::Break(res) => res,
-            _ => panic!("{}", message),
-        }
-    }
-}
-
-trait FoldUntil: Iterator + Sized {
-    fn fold_until<F, T>(self, mut acc: T, mut fold: F) -> Until<T>
-    where
-        F: FnMut(&mut T, Self::Item) -> Until<T>,
-    {
-        for item in self {
-            match fold(&mut acc, item) {
-                Until::Continue => continue,
-                Until::Break(res) => return Until::Break(res),
-            }
-        }
-        Until::Continue
-    }
-}
-
-impl<I: Iterator> FoldUntil for I {}
-
 #[cfg(test)]
 mod tests {
     use pretty_assertions::assert_eq;
@@ -88,7 +52,7 @@ mod tests {
     #[case("))(((((", 1)]
     #[case("())", 3)]
     #[case("(())())", 7)]
-    fn test_part_two(#[case] input: &str, #[case] expected: i32) {
+    fn test_part_two(#[case] input: &str, #[case] expected: usize) {
         assert_eq!(solve_two(input), expected);
     }
 
@@ -101,38 +65,6 @@ mod tests {
         solve_two(input);
     }
 
-    #[test]
-    fn test_fold_until() {
-        let input = [1, 2, 3, 4, 5];
-        let result = input.iter().fold_until(0, |acc, &x| {
-            *acc += x;
-            if *acc > 5 {
-                Until::Break(*acc)
-            } else {
-                Until::Continue
-            }
-        });
-
-        assert_eq!(result, Until::Break(6));
-    }
-
-    #[test]
-    #[should_panic(expected = "test")]
-    fn test_fold_until_panic() {
-        let input = [1, 2, 3, 4, 5];
-        let _ = input
-            .iter()
-            .fold_until(0, |_, _| Until::Continue)
-            .expect_break("test");
-    }
-
-    #[test]
-    fn test_fold_until_continue() {
-        let input = [1, 2, 3, 4, 5];
-        let result = input.iter().fold_until(0, |_, _| Until::Continue);
-        assert_eq!(result, Until::Continue);
-    }
-
     const INPUT: &str = input!("day1");
 
     #[test]
@@ -144,4 +76,10 @@ mod tests {
     fn integration_part_two() {
         assert_eq!(solve_two(INPUT), 1783);
     }
+
+    #[test]
+    fn integration_all_inputs() {
+        input::check_inputs("day1", 1, solve_one);
+        input::check_inputs("day1", 2, solve_two);
+    }
 }
@@ -1,42 +1,23 @@
 use aoc_runner_derive::aoc;
 
-#[derive(Debug, PartialEq, Eq)]
-struct LookAndSay {
-    num: char,
-    qt: usize,
-}
-
-fn look_and_say(input: &str) -> String {
-    let mut number_by_quantity = Vec::new();
-
-    for (i, num) in input.chars().enumerate() {
-        if i == 0 {
-            number_by_quantity.push(LookAndSay { num, qt: 1 });
-        } else {
-            let last = number_by_quantity.last_mut().unwrap();
-            if last.num == num {
-                last.qt += 1;
-            } else {
-                number_by_quantity.push(LookAndSay { num, qt: 1 });
-            }
-        }
-    }
+use crate::rle;
 
-    let mut result = String::new();
-    for LookAndSay { num, qt } in number_by_quantity {
-        result.push_str(&qt.to_string());
-        result.push(num);
-    }
-
-    result
+fn look_and_say(digits: &[u8]) -> Vec<u8> {
+    rle::encode_digits(digits)
 }
 
-fn play_game(input: &str, rounds: usize) -> String {
-    let mut result = input.to_string();
+fn play_game(input: &str, rounds: usize) -> Vec<u8> {
+    let mut digits = input
+        .bytes()
+        .map(|b| {
+            assert!(b.is_ascii_digit(), "Not a digit: {}", b as char);
+            b - b'0'
+        })
+        .collect::<Vec<_>>();
     for _ in 0..rounds {
-        result = look_and_say(&result);
+        digits = look_and_say(&digits);
     }
-    result
+    digits
 }
 
 #[aoc(day10, part1)]
@@ -59,15 +40,27 @@ mod tests {
     use super::*;
 
     #[rstest]
-    #[case("1", "11")]
-    #[case("11", "21")]
-    #[case("21", "1211")]
-    #[case("1211", "111221")]
-    #[case("111221", "312211")]
-    fn test_look_and_say(#[case] input: &str, #[case] expected: &str) {
+    #[case(&[1], &[1, 1])]
+    #[case(&[1, 1], &[2, 1])]
+    #[case(&[2, 1], &[1, 2, 1, 1])]
+    #[case(&[1, 2, 1, 1], &[1, 1, 1, 2, 2, 1])]
+    #[case(&[1, 1, 1, 2, 2, 1], &[3, 1, 2, 2, 1, 1])]
+    fn test_look_and_say(#[case] input: &[u8], #[case] expected: &[u8]) {
         assert_eq!(look_and_say(input), expected);
     }
 
+    #[test]
+    fn test_play_game() {
+        assert_eq!(play_game("1", 5), [3, 1, 2, 2, 1, 1]);
+        assert_eq!(play_game("1", 0), [1]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Not a digit: x")]
+    fn not_a_digit() {
+        play_game("1x", 1);
+    }
+
     const INPUT: &str = input!("day10");
 
     #[test]
@@ -79,4 +72,10 @@ mod tests {
     fn integration_part_two() {
         assert_eq!(solve_two(INPUT.trim()), 3579328);
     }
+
+    #[test]
+    fn integration_all_inputs() {
+        input::check_inputs("day10", 1, |input| solve_one(input.trim()));
+        input::check_inputs("day10", 2, |input| solve_two(input.trim()));
+    }
 }
@@ -116,6 +116,12 @@ mod tests {
         assert_eq!(solve_two(&parse(INPUT)), 3783758);
     }
 
+    #[test]
+    fn integration_all_inputs() {
+        input::check_inputs("day2", 1, |input| solve_one(&parse(input)));
+        input::check_inputs("day2", 2, |input| solve_two(&parse(input)));
+    }
+
     #[test]
     fn test_area() {
         assert_eq!(gift!("2x3x4").area(), 58);
@@ -1,49 +1,9 @@
 use aoc_runner_derive::{aoc, aoc_generator};
-use derive_more::derive::Display;
-
-use crate::set;
-
-#[derive(Debug, PartialEq, Clone, Copy, Display)]
-enum Direction {
-    #[display("^")]
-    Up,
-    #[display("v")]
-    Down,
-    #[display(">")]
-    Left,
-    #[display("<")]
-    Right,
-}
 
-impl From<char> for Direction {
-    fn from(value: char) -> Self {
-        match value {
-            '^' => Direction::Up,
-            'v' => Direction::Down,
-            '>' => Direction::Right,
-            '<' => Direction::Left,
-            ch => panic!("This is not a valid direction: {}", ch),
-        }
-    }
-}
-
-#[derive(Debug, Default, PartialEq, Clone, Copy, Display, Hash, Eq)]
-#[display("({x}, {y})")]
-struct Position {
-    x: i32,
-    y: i32,
-}
-
-impl Position {
-    fn inplace_move(&mut self, direction: &Direction) {
-        match direction {
-            Direction::Up => self.y += 1,
-            Direction::Down => self.y -= 1,
-            Direction::Left => self.x -= 1,
-            Direction::Right => self.x += 1,
-        }
-    }
-}
+use crate::{
+    geometry::{Direction, Vec2},
+    set,
+};
 
 #[aoc_generator(day3)]
 fn parse(input: &str) -> Vec<Direction> {
@@ -52,11 +12,11 @@ fn parse(input: &str) -> Vec<Direction> {
 
 #[aoc(day3, part1)]
 fn solve_one(directions: &[Direction]) -> usize {
-    let mut position = Position::default();
+    let mut position = Vec2::ORIGIN;
     let mut visited_houses = set![position];
 
     for direction in directions {
-        position.inplace_move(direction);
+        position += direction.offset();
         visited_houses.insert(position);
     }
 
@@ -65,17 +25,17 @@ fn solve_one(directions: &[Direction]) -> usize {
 
 #[aoc(day3, part2)]
 fn solve_two(directions: &[Direction]) -> usize {
-    let mut santa_position = Position::default();
-    let mut robo_santa_position = Position::default();
+    let mut santa_position = Vec2::ORIGIN;
+    let mut robo_santa_position = Vec2::ORIGIN;
 
     let mut visited_houses = set![santa_position];
 
     for (i, direction) in directions.iter().enumerate() {
         if i % 2 == 0 {
-            santa_position.inplace_move(direction);
+            santa_position += direction.offset();
             visited_houses.insert(santa_position);
         } else {
-            robo_santa_position.inplace_move(direction);
+            robo_santa_position += direction.offset();
             visited_houses.insert(robo_santa_position);
         }
     }
@@ -104,4 +64,10 @@ mod tests {
     fn integration_part_two() {
         assert_eq!(solve_two(&parse(INPUT)), 2631);
     }
+
+    #[test]
+    fn integration_all_inputs() {
+        input::check_inputs("day3", 1, |input| solve_one(&parse(input)));
+        input::check_inputs("day3", 2, |input| solve_two(&parse(input)));
+    }
 }
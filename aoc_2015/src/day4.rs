@@ -1,25 +1,21 @@
 use aoc_runner_derive::aoc;
 
+use crate::miner::Miner;
+
 #[aoc(day4, part1)]
 fn solve_one(input: &str) -> usize {
-    for i in 0.. {
-        let hash = format!("{:x}", md5::compute(format!("{}{}", input, i)));
-        if hash.starts_with("00000") {
-            return i;
-        }
-    }
-    panic!("No solution found");
+    mine(input, 5)
 }
 
 #[aoc(day4, part2)]
 fn solve_two(input: &str) -> usize {
-    for i in 0.. {
-        let hash = format!("{:x}", md5::compute(format!("{}{}", input, i)));
-        if hash.starts_with("000000") {
-            return i;
-        }
-    }
-    panic!("No solution found");
+    mine(input, 6)
+}
+
+fn mine(key: &str, zeros: u32) -> usize {
+    Miner::new(key)
+        .find_zeros(zeros)
+        .expect("No solution found") as usize
 }
 
 // I implemented this challenge before I actually started writing in Rust,
@@ -43,4 +39,10 @@ mod tests {
     fn integration_part_two() {
         assert_eq!(solve_two(INPUT.trim()), 1038736);
     }
+
+    #[test]
+    fn integration_all_inputs() {
+        input::check_inputs("day4", 1, |input| solve_one(input.trim()));
+        input::check_inputs("day4", 2, |input| solve_two(input.trim()));
+    }
 }
@@ -1,75 +1,41 @@
-use std::collections::HashMap;
-
 use aoc_runner_derive::aoc;
-use itertools::Itertools;
-
-struct Letter(String);
-
-fn has_three_vowels(s: &str) -> bool {
-    let is_vowel = |c| matches!(c, 'a' | 'e' | 'i' | 'o' | 'u');
-    s.chars().filter(|&c| is_vowel(c)).count() >= 3
-}
-
-fn has_double(s: &str) -> bool {
-    s.chars()
-        .tuple_windows::<(char, char)>()
-        .any(|(a, b)| a == b)
-}
-
-fn is_not_in_blacklist(s: &str) -> bool {
-    !s.contains("ab") && !s.contains("cd") && !s.contains("pq") && !s.contains("xy")
-}
 
-fn has_repeated_pair_with_inner(s: &str) -> bool {
-    s.chars()
-        .tuple_windows::<(char, char, char)>()
-        .any(|(a, _, c)| a == c)
-}
-
-fn has_pair_twice(s: &str) -> bool {
-    let mut seen_pairs: HashMap<&str, usize> = HashMap::new();
-    let chars = s.as_bytes();
-
-    for i in 0..chars.len().saturating_sub(1) {
-        let pair = &s[i..i + 2];
-        if let Some(&prev_index) = seen_pairs.get(pair) {
-            if prev_index + 1 < i {
-                return true;
-            }
-        } else {
-            seen_pairs.insert(pair, i);
-        }
-    }
+use crate::rules::{self, Rule};
 
-    false
-}
+struct Letter(String);
 
 impl Letter {
     fn new(s: &str) -> Self {
         Self(s.to_string())
     }
 
-    fn is_nice(&self, rules: Vec<fn(&str) -> bool>) -> bool {
-        rules.iter().all(|rule| rule(&self.0))
+    fn is_nice(&self, policy: &Rule) -> bool {
+        policy.matches(&self.0)
     }
 }
 
-#[aoc(day5, part1, Clearer)]
-fn solve_one(input: &str) -> usize {
+/// The rule of a file in [`rules::rules_dir`], read on each run so that it
+/// can change without recompiling.
+fn policy(file: &str) -> Rule {
+    Rule::load(&rules::rules_dir().join(file)).unwrap_or_else(|e| panic!("{}", e))
+}
+
+fn count_nice(input: &str, policy: &Rule) -> usize {
     input
         .lines()
         .map(Letter::new)
-        .filter(|l| l.is_nice(vec![has_three_vowels, has_double, is_not_in_blacklist]))
+        .filter(|l| l.is_nice(policy))
         .count()
 }
 
+#[aoc(day5, part1, Clearer)]
+fn solve_one(input: &str) -> usize {
+    count_nice(input, &policy("day5-part1.rules"))
+}
+
 #[aoc(day5, part2)]
 fn solve_two(input: &str) -> usize {
-    input
-        .lines()
-        .map(Letter::new)
-        .filter(|l| l.is_nice(vec![has_repeated_pair_with_inner, has_pair_twice]))
-        .count()
+    count_nice(input, &policy("day5-part2.rules"))
 }
 
 // I implemented this challenge before I actually started writing in Rust,
@@ -77,6 +43,7 @@ fn solve_two(input: &str) -> usize {
 #[cfg(test)]
 mod tests {
     use pretty_assertions::assert_eq;
+    use rstest::rstest;
 
     use crate::input;
 
@@ -94,43 +61,80 @@ mod tests {
         assert_eq!(solve_two(INPUT), 53);
     }
 
+    #[test]
+    fn integration_all_inputs() {
+        input::check_inputs("day5", 1, solve_one);
+        input::check_inputs("day5", 2, solve_two);
+    }
+
+    fn rule(source: &str) -> Rule {
+        source.parse().unwrap()
+    }
+
+    #[rstest]
+    #[case("ugknbfddgicrmopn", true)]
+    #[case("aaa", true)]
+    #[case("jchzalrnumimnmhp", false)]
+    #[case("haegwjzuvuyypxyu", false)]
+    #[case("dvszwmarrgswjxmb", false)]
+    fn example_part_one(#[case] s: &str, #[case] nice: bool) {
+        assert_eq!(Letter::new(s).is_nice(&policy("day5-part1.rules")), nice);
+    }
+
+    #[rstest]
+    #[case("qjhvhtzxzqqjkmpb", true)]
+    #[case("xxyxx", true)]
+    #[case("uurcxstgmygtbstg", false)]
+    #[case("ieodomkazucvgmuy", false)]
+    fn example_part_two(#[case] s: &str, #[case] nice: bool) {
+        assert_eq!(Letter::new(s).is_nice(&policy("day5-part2.rules")), nice);
+    }
+
     #[test]
     fn test_has_three_vowels() {
-        assert_eq!(has_three_vowels("aei"), true);
-        assert_eq!(has_three_vowels("xazegov"), true);
-        assert_eq!(has_three_vowels("aeiouaeiouaeiou"), true);
-        assert_eq!(has_three_vowels("dvszwmarrgswjxmb"), false);
+        let has_three_vowels = rule(r#"at_least(3, "aeiou")"#);
+        assert_eq!(has_three_vowels.matches("aei"), true);
+        assert_eq!(has_three_vowels.matches("xazegov"), true);
+        assert_eq!(has_three_vowels.matches("aeiouaeiouaeiou"), true);
+        assert_eq!(has_three_vowels.matches("dvszwmarrgswjxmb"), false);
     }
 
     #[test]
     fn test_has_double() {
-        assert_eq!(has_double("xx"), true);
-        assert_eq!(has_double("abcdde"), true);
-        assert_eq!(has_double("aabbccdd"), true);
-        assert_eq!(has_double("jchzalrnumimnmhp"), false);
+        let has_double = rule("run(2)");
+        assert_eq!(has_double.matches("xx"), true);
+        assert_eq!(has_double.matches("abcdde"), true);
+        assert_eq!(has_double.matches("aabbccdd"), true);
+        assert_eq!(has_double.matches("jchzalrnumimnmhp"), false);
     }
 
     #[test]
     fn test_is_not_in_blacklist() {
-        assert_eq!(is_not_in_blacklist("abcd"), false);
-        assert_eq!(is_not_in_blacklist("pqrs"), false);
-        assert_eq!(is_not_in_blacklist("xyzt"), false);
-        assert_eq!(is_not_in_blacklist("ugknbfddgicrmopn"), true);
+        let is_not_in_blacklist = rule(r#"forbid("ab", "cd", "pq", "xy")"#);
+        assert_eq!(is_not_in_blacklist.matches("abcd"), false);
+        assert_eq!(is_not_in_blacklist.matches("pqrs"), false);
+        assert_eq!(is_not_in_blacklist.matches("xyzt"), false);
+        assert_eq!(is_not_in_blacklist.matches("ugknbfddgicrmopn"), true);
     }
 
     #[test]
     fn test_has_repeated_pair_with_inner() {
-        assert_eq!(has_repeated_pair_with_inner("xyx"), true);
-        assert_eq!(has_repeated_pair_with_inner("abcdefeghi"), true);
-        assert_eq!(has_repeated_pair_with_inner("aaa"), true);
-        assert_eq!(has_repeated_pair_with_inner("uurcxstgmygtbstg"), false);
+        let has_repeated_pair_with_inner = rule(r#"pattern("x?x")"#);
+        assert_eq!(has_repeated_pair_with_inner.matches("xyx"), true);
+        assert_eq!(has_repeated_pair_with_inner.matches("abcdefeghi"), true);
+        assert_eq!(has_repeated_pair_with_inner.matches("aaa"), true);
+        assert_eq!(
+            has_repeated_pair_with_inner.matches("uurcxstgmygtbstg"),
+            false
+        );
     }
 
     #[test]
     fn test_has_pair_twice() {
-        assert_eq!(has_pair_twice("xyxy"), true);
-        assert_eq!(has_pair_twice("aabcdefgaa"), true);
-        assert_eq!(has_pair_twice("aaa"), false);
-        assert_eq!(has_pair_twice("ieodomkazucvgmuy"), false);
+        let has_pair_twice = rule("pair_twice(0)");
+        assert_eq!(has_pair_twice.matches("xyxy"), true);
+        assert_eq!(has_pair_twice.matches("aabcdefgaa"), true);
+        assert_eq!(has_pair_twice.matches("aaa"), false);
+        assert_eq!(has_pair_twice.matches("ieodomkazucvgmuy"), false);
     }
 }
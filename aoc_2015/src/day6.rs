@@ -1,13 +1,11 @@
 use aoc_runner_derive::aoc;
 use nom::{
-    branch::alt,
-    bytes::complete::tag,
-    character::complete::{char, space1, u64},
-    combinator::map,
-    sequence::{separated_pair, tuple},
-    IResult,
+    branch::alt, bytes::complete::tag, character::complete::space1, combinator::map,
+    sequence::tuple, IResult,
 };
 
+use crate::{bitgrid::BitGrid, grid::Grid, parse};
+
 struct Point {
     x: usize,
     y: usize,
@@ -27,14 +25,7 @@ fn parse_instruction(input: &str) -> IResult<&str, Instruction> {
 }
 
 fn parse_point(input: &str) -> IResult<&str, Point> {
-    let (input, (x, y)) = separated_pair(u64, char(','), u64)(input)?;
-    Ok((
-        input,
-        Point {
-            x: x as usize,
-            y: y as usize,
-        },
-    ))
+    map(parse::point, |(x, y)| Point { x, y })(input)
 }
 
 impl Command {
@@ -69,18 +60,13 @@ struct Coordinate {
     to: Point,
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Default)]
 enum LightKind {
     Dimmer,
+    #[default]
     Default,
 }
 
-impl Default for LightKind {
-    fn default() -> Self {
-        Self::Default
-    }
-}
-
 #[derive(Clone, Copy, Default)]
 struct Light {
     kind: LightKind,
@@ -108,44 +94,114 @@ impl Light {
     }
 }
 
-struct Grid {
-    flatten: Vec<Light>,
-    size: usize,
+const SIZE: usize = 1000;
+
+fn lights(light_kind: LightKind, size: usize) -> Grid<Light> {
+    Grid::new(size, size, Light::new(light_kind))
+}
+
+trait Lights {
+    fn apply(&mut self, command: &Command);
+
+    fn sum(&self) -> usize;
 }
 
-impl Grid {
-    fn new(light_kind: LightKind) -> Self {
-        Self::new_with_size(light_kind, 1000)
+impl Lights for Grid<Light> {
+    fn apply(&mut self, command: &Command) {
+        let Coordinate { from, to } = &command.coordinate;
+        self.rect_mut(from.x, from.y, to.x - from.x + 1, to.y - from.y + 1)
+            .for_each(|light| light.apply(&command.instruction));
     }
 
-    fn new_with_size(light_kind: LightKind, size: usize) -> Self {
-        Self {
-            flatten: vec![Light::new(light_kind); size * size],
-            size,
-        }
+    fn sum(&self) -> usize {
+        self.iter().map(|light| light.level).sum()
+    }
+}
+
+/// The lights grouped into blocks that every command covers either entirely
+/// or not at all, found by cutting the grid along the edges of every command.
+///
+/// Applying a command visits its blocks instead of its lights, so the cost
+/// grows with the number of commands, not with the size of the grid.
+struct Blocks {
+    /// The first column of each block, then the column past the last block.
+    xs: Vec<usize>,
+    /// The first row of each block, then the row past the last block.
+    ys: Vec<usize>,
+    lights: Grid<Light>,
+}
+
+impl Blocks {
+    fn new(light_kind: LightKind, commands: &[Command]) -> Self {
+        let edges = |axis: fn(&Point) -> usize| {
+            let mut edges: Vec<_> = commands
+                .iter()
+                .flat_map(|c| [axis(&c.coordinate.from), axis(&c.coordinate.to) + 1])
+                .collect();
+            edges.sort_unstable();
+            edges.dedup();
+            edges
+        };
+        let xs = edges(|p| p.x);
+        let ys = edges(|p| p.y);
+        let lights = Grid::new(
+            xs.len().saturating_sub(1),
+            ys.len().saturating_sub(1),
+            Light::new(light_kind),
+        );
+
+        Self { xs, ys, lights }
     }
 
-    fn find(&mut self, x: usize, y: usize) -> &mut Light {
-        &mut self.flatten[x + y * self.size]
+    /// The blocks spanning `from..=to` along an axis cut at `edges`.
+    fn span(edges: &[usize], from: usize, to: usize) -> (usize, usize) {
+        let first = edges
+            .binary_search(&from)
+            .expect("commands start on an edge");
+        let last = edges
+            .binary_search(&(to + 1))
+            .expect("commands end on an edge");
+        (first, last - first)
     }
+}
 
+impl Lights for Blocks {
     fn apply(&mut self, command: &Command) {
-        for x in command.coordinate.from.x..=command.coordinate.to.x {
-            for y in command.coordinate.from.y..=command.coordinate.to.y {
-                let light = self.find(x, y);
-                light.apply(&command.instruction);
-            }
-        }
+        let Coordinate { from, to } = &command.coordinate;
+        let (x, width) = Self::span(&self.xs, from.x, to.x);
+        let (y, height) = Self::span(&self.ys, from.y, to.y);
+        self.lights
+            .rect_mut(x, y, width, height)
+            .for_each(|light| light.apply(&command.instruction));
     }
 
     fn sum(&self) -> usize {
-        self.flatten.iter().map(|light| light.level).sum()
+        let widths: Vec<_> = self.xs.windows(2).map(|w| w[1] - w[0]).collect();
+        let heights = self.ys.windows(2).map(|h| h[1] - h[0]);
+        self.lights
+            .rows()
+            .zip(heights)
+            .map(|(row, height)| {
+                row.iter()
+                    .zip(&widths)
+                    .map(|(light, width)| light.level * width * height)
+                    .sum::<usize>()
+            })
+            .sum()
     }
 }
 
+fn solve_blocks(light_kind: LightKind, input: &str) -> usize {
+    let commands: Vec<_> = input.lines().map(Command::from).collect();
+    let mut blocks = Blocks::new(light_kind, &commands);
+    commands.iter().for_each(|command| blocks.apply(command));
+
+    blocks.sum()
+}
+
 #[aoc(day6, part1)]
 fn solve_one(input: &str) -> usize {
-    let mut grid = Grid::new(LightKind::Default);
+    let mut grid = lights(LightKind::Default, SIZE);
     let commands = input.lines().map(Command::from);
     commands
         .into_iter()
@@ -154,9 +210,35 @@ fn solve_one(input: &str) -> usize {
     grid.sum()
 }
 
+/// Part one on a [`BitGrid`], as the on/off lights need a bit each rather
+/// than a whole [`Light`], and rectangles update 64 lights per word operation.
+///
+/// The `day6` bench times it against the grid of [`Light`]s on the same
+/// random commands: `cargo bench --bench day6`.
+#[aoc(day6, part1, Bits)]
+fn solve_one_bits(input: &str) -> usize {
+    let mut grid = BitGrid::new(SIZE, SIZE);
+    for command in input.lines().map(Command::from) {
+        let Coordinate { from, to } = &command.coordinate;
+        let (width, height) = (to.x - from.x + 1, to.y - from.y + 1);
+        match command.instruction {
+            Instruction::TurnOn => grid.set_rect(from.x, from.y, width, height, true),
+            Instruction::TurnOff => grid.set_rect(from.x, from.y, width, height, false),
+            Instruction::Toggle => grid.toggle_rect(from.x, from.y, width, height),
+        }
+    }
+
+    grid.count_ones()
+}
+
+#[aoc(day6, part1, Blocks)]
+fn solve_one_blocks(input: &str) -> usize {
+    solve_blocks(LightKind::Default, input)
+}
+
 #[aoc(day6, part2)]
 fn solve_two(input: &str) -> usize {
-    let mut grid = Grid::new(LightKind::Dimmer);
+    let mut grid = lights(LightKind::Dimmer, SIZE);
     let commands = input.lines().map(Command::from);
     commands
         .into_iter()
@@ -165,6 +247,11 @@ fn solve_two(input: &str) -> usize {
     grid.sum()
 }
 
+#[aoc(day6, part2, Blocks)]
+fn solve_two_blocks(input: &str) -> usize {
+    solve_blocks(LightKind::Dimmer, input)
+}
+
 #[cfg(test)]
 mod tests {
     use pretty_assertions::assert_eq;
@@ -185,6 +272,104 @@ mod tests {
         assert_eq!(solve_two(INPUT), 14687245);
     }
 
+    #[test]
+    fn integration_all_inputs() {
+        input::check_inputs("day6", 1, solve_one);
+        input::check_inputs("day6", 1, solve_one_bits);
+        input::check_inputs("day6", 1, solve_one_blocks);
+        input::check_inputs("day6", 2, solve_two_blocks);
+        input::check_inputs("day6", 2, solve_two);
+    }
+
+    #[test]
+    fn bits_match_lights() {
+        let input = "\
+turn on 0,0 through 999,999
+toggle 0,0 through 999,0
+turn off 499,499 through 500,500
+toggle 63,2 through 700,900
+turn off 1,1 through 64,64";
+
+        assert_eq!(solve_one_bits(input), solve_one(input));
+    }
+
+    /// `count` commands over the whole grid, from a xorshift generator.
+    fn random_commands(mut seed: u64, count: usize) -> String {
+        let mut next = |bound: usize| {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            (seed >> 24) as usize % bound
+        };
+        (0..count)
+            .map(|_| {
+                let instruction = ["turn on", "turn off", "toggle"][next(3)];
+                let (x0, x1) = (next(SIZE), next(SIZE));
+                let (y0, y1) = (next(SIZE), next(SIZE));
+                format!(
+                    "{} {},{} through {},{}",
+                    instruction,
+                    x0.min(x1),
+                    y0.min(y1),
+                    x0.max(x1),
+                    y0.max(y1)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn bits_match_lights_on_random_commands() {
+        let input = random_commands(0x2015_0006, 20);
+        assert_eq!(solve_one_bits(&input), solve_one(&input));
+    }
+
+    #[test]
+    fn bits_are_smaller_than_lights() {
+        let bits = BitGrid::new(SIZE, SIZE).size_in_bytes();
+        let lights = std::mem::size_of::<Light>() * SIZE * SIZE;
+        assert_eq!(bits, SIZE.div_ceil(64) * 8 * SIZE);
+        assert!(bits * 100 < lights, "{} bytes against {}", bits, lights);
+    }
+
+    #[test]
+    fn blocks_match_lights() {
+        let input = "\
+turn on 0,0 through 999,999
+toggle 0,0 through 999,0
+turn off 499,499 through 500,500
+toggle 63,2 through 700,900
+turn off 1,1 through 64,64
+toggle 999,999 through 999,999
+turn on 10,500 through 20,999
+turn off 15,0 through 15,999";
+
+        for i in 1..=input.lines().count() {
+            let commands = input.lines().take(i).collect::<Vec<_>>().join("\n");
+            assert_eq!(solve_one_blocks(&commands), solve_one(&commands));
+            assert_eq!(solve_two_blocks(&commands), solve_two(&commands));
+        }
+        assert_eq!(solve_one_blocks(""), 0);
+    }
+
+    #[test]
+    fn blocks_on_a_huge_grid() {
+        let input = "\
+turn on 0,0 through 999999999,999999999
+toggle 0,0 through 999999999,0
+turn off 499999,499999 through 500000,500000";
+
+        assert_eq!(
+            solve_one_blocks(input),
+            1_000_000_000 * 1_000_000_000 - 1_000_000_000 - 4
+        );
+        assert_eq!(
+            solve_two_blocks(input),
+            1_000_000_000 * 1_000_000_000 + 2 * 1_000_000_000 - 4
+        );
+    }
+
     #[test]
     fn parse_turn_on_command() {
         let cmd = Command::from("turn on 0,0 through 999,999");
@@ -276,23 +461,23 @@ mod tests {
 
     #[test]
     fn grid_turn_on_small_region() {
-        let mut grid = Grid::new_with_size(LightKind::Default, 3);
+        let mut grid = lights(LightKind::Default, 3);
 
         let cmd = Command::from("turn on 0,0 through 1,1");
         grid.apply(&cmd);
 
         for x in 0..=1 {
             for y in 0..=1 {
-                assert_eq!(grid.find(x, y).level, 1, "({x},{y}) should be ON");
+                assert_eq!(grid[(x, y)].level, 1, "({x},{y}) should be ON");
             }
         }
 
-        assert_eq!(grid.find(2, 2).level, 0);
+        assert_eq!(grid[(2, 2)].level, 0);
     }
 
     #[test]
     fn grid_turn_off_small_region() {
-        let mut grid = Grid::new_with_size(LightKind::Default, 3);
+        let mut grid = lights(LightKind::Default, 3);
 
         let cmd_on = Command::from("turn on 0,0 through 1,1");
         grid.apply(&cmd_on);
@@ -300,42 +485,42 @@ mod tests {
         let cmd_off = Command::from("turn off 1,1 through 2,2");
         grid.apply(&cmd_off);
 
-        assert_eq!(grid.find(1, 1).level, 0);
+        assert_eq!(grid[(1, 1)].level, 0);
 
-        assert_eq!(grid.find(2, 2).level, 0);
+        assert_eq!(grid[(2, 2)].level, 0);
 
-        assert_eq!(grid.find(0, 0).level, 1);
+        assert_eq!(grid[(0, 0)].level, 1);
     }
 
     #[test]
     fn grid_toggle_small_region() {
-        let mut grid = Grid::new_with_size(LightKind::Default, 3);
+        let mut grid = lights(LightKind::Default, 3);
 
         let cmd_toggle = Command::from("toggle 0,0 through 1,0");
         grid.apply(&cmd_toggle);
 
-        assert_eq!(grid.find(0, 0).level, 1);
-        assert_eq!(grid.find(1, 0).level, 1);
+        assert_eq!(grid[(0, 0)].level, 1);
+        assert_eq!(grid[(1, 0)].level, 1);
 
         grid.apply(&cmd_toggle);
-        assert_eq!(grid.find(0, 0).level, 0);
-        assert_eq!(grid.find(1, 0).level, 0);
+        assert_eq!(grid[(0, 0)].level, 0);
+        assert_eq!(grid[(1, 0)].level, 0);
     }
 
     #[test]
     fn grid_dimmer_turn_off_at_zero() {
-        let mut grid = Grid::new_with_size(LightKind::Dimmer, 3);
+        let mut grid = lights(LightKind::Dimmer, 3);
 
         let cmd_off = Command::from("turn off 0,0 through 0,0");
         grid.apply(&cmd_off);
-        assert_eq!(grid.find(0, 0).level, 0);
+        assert_eq!(grid[(0, 0)].level, 0);
 
         let cmd_on = Command::from("turn on 0,0 through 0,0");
         grid.apply(&cmd_on);
-        assert_eq!(grid.find(0, 0).level, 1);
+        assert_eq!(grid[(0, 0)].level, 1);
 
         grid.apply(&cmd_off);
-        assert_eq!(grid.find(0, 0).level, 0);
+        assert_eq!(grid[(0, 0)].level, 0);
     }
 
     #[test]
@@ -1,28 +1,36 @@
 use aoc_runner_derive::aoc;
+use itertools::Itertools;
 use nom::{
     branch::alt,
     bytes::complete::tag,
-    character::complete::{alpha1, digit1, space1},
-    combinator::map_res,
+    character::complete::space1,
+    combinator::map,
     sequence::{separated_pair, tuple},
     IResult,
 };
-use std::collections::HashMap;
 
+use crate::{
+    interner::{Interner, Symbol},
+    memo::{Memo, Need, Resolver},
+    parse::{identifier, unsigned},
+};
+
+/// An operand naming its wire with `W`: its name once parsed, and its symbol
+/// once in a [`Circuit`].
 #[derive(Debug, Clone)]
-enum Operand {
-    Wire(String),
+enum Operand<W = String> {
+    Wire(W),
     Value(u16),
 }
 
 #[derive(Debug, Clone)]
-enum Operation {
-    And { lhs: Operand, rhs: Operand },
-    Or { lhs: Operand, rhs: Operand },
-    Not { rhs: Operand },
-    LShift { lhs: Operand, rhs: Operand },
-    RShift { lhs: Operand, rhs: Operand },
-    Assign { operand: Operand },
+enum Operation<W = String> {
+    And { lhs: Operand<W>, rhs: Operand<W> },
+    Or { lhs: Operand<W>, rhs: Operand<W> },
+    Not { rhs: Operand<W> },
+    LShift { lhs: Operand<W>, rhs: Operand<W> },
+    RShift { lhs: Operand<W>, rhs: Operand<W> },
+    Assign { operand: Operand<W> },
 }
 
 #[derive(Debug, Clone)]
@@ -39,7 +47,7 @@ impl Instruction {
 
 fn parse_instruction(input: &str) -> IResult<&str, Instruction> {
     let (input, (operation, _, _, _, output_wire)) =
-        tuple((parse_operation, space1, tag("->"), space1, alpha1))(input)?;
+        tuple((parse_operation, space1, tag("->"), space1, identifier))(input)?;
 
     Ok((
         input,
@@ -93,81 +101,114 @@ fn parse_assign(input: &str) -> IResult<&str, Operation> {
 
 fn parse_operand(input: &str) -> IResult<&str, Operand> {
     alt((
-        map_res(digit1, |s: &str| s.parse::<u16>().map(Operand::Value)),
-        map_res(alpha1, |s: &str| -> Result<Operand, ()> {
-            Ok(Operand::Wire(s.to_string()))
-        }),
+        map(unsigned, Operand::Value),
+        map(identifier, |s: &str| Operand::Wire(s.to_string())),
     ))(input)
 }
 
+impl<W> Operand<W> {
+    fn map_wire<V>(&self, f: &mut impl FnMut(&W) -> V) -> Operand<V> {
+        match self {
+            Operand::Wire(wire) => Operand::Wire(f(wire)),
+            Operand::Value(n) => Operand::Value(*n),
+        }
+    }
+}
+
+impl<W> Operation<W> {
+    fn map_wires<V>(&self, mut f: impl FnMut(&W) -> V) -> Operation<V> {
+        let mut f = |operand: &Operand<W>| operand.map_wire(&mut f);
+        match self {
+            Operation::And { lhs, rhs } => Operation::And {
+                lhs: f(lhs),
+                rhs: f(rhs),
+            },
+            Operation::Or { lhs, rhs } => Operation::Or {
+                lhs: f(lhs),
+                rhs: f(rhs),
+            },
+            Operation::Not { rhs } => Operation::Not { rhs: f(rhs) },
+            Operation::LShift { lhs, rhs } => Operation::LShift {
+                lhs: f(lhs),
+                rhs: f(rhs),
+            },
+            Operation::RShift { lhs, rhs } => Operation::RShift {
+                lhs: f(lhs),
+                rhs: f(rhs),
+            },
+            Operation::Assign { operand } => Operation::Assign {
+                operand: f(operand),
+            },
+        }
+    }
+}
+
+/// The wires of a circuit, numbered by an [`Interner`] so that gates and
+/// signals are looked up by index.
 struct Circuit {
-    wires: HashMap<String, u16>,
-    instructions: HashMap<String, Operation>,
+    wires: Interner,
+    gates: Vec<Option<Operation<Symbol>>>,
+    signals: Memo<Symbol, u16>,
 }
 
 impl Circuit {
     fn new(instructions: Vec<Instruction>) -> Self {
-        let mut instr_map = HashMap::new();
-        for i in instructions {
-            instr_map.insert(i.output, i.operation);
+        let mut wires = Interner::new();
+        let gates: Vec<_> = instructions
+            .iter()
+            .map(|i| {
+                let output = wires.intern(&i.output);
+                (output, i.operation.map_wires(|wire| wires.intern(wire)))
+            })
+            .collect();
+
+        let mut by_output = vec![None; wires.len()];
+        for (output, operation) in gates {
+            by_output[output.index()] = Some(operation);
         }
+
         Self {
-            wires: HashMap::new(),
-            instructions: instr_map,
+            wires,
+            gates: by_output,
+            signals: Memo::new(),
         }
     }
 
+    /// The signal on `wire`, computing only the wires it depends on. Wires
+    /// without a gate carry 0.
     fn get_value(&mut self, wire: &str) -> u16 {
-        if let Some(&val) = self.wires.get(wire) {
-            return val;
-        }
-
-        let operation = match self.instructions.get(wire) {
-            None => {
-                self.wires.insert(wire.to_string(), 0);
-                return 0;
-            }
-            Some(op) => op.clone(),
+        let Some(symbol) = self.wires.get(wire) else {
+            return 0;
         };
-
-        let val = match operation {
-            Operation::Assign { operand } => self.evaluate_operand(&operand),
-            Operation::And { lhs, rhs } => {
-                let l = self.evaluate_operand(&lhs);
-                let r = self.evaluate_operand(&rhs);
-                l & r
-            }
-            Operation::Or { lhs, rhs } => {
-                let l = self.evaluate_operand(&lhs);
-                let r = self.evaluate_operand(&rhs);
-                l | r
-            }
-            Operation::Not { rhs } => {
-                let v = self.evaluate_operand(&rhs);
-                !v
-            }
-            Operation::LShift { lhs, rhs } => {
-                let l = self.evaluate_operand(&lhs);
-                let bits = self.evaluate_operand(&rhs);
-                l << bits
-            }
-            Operation::RShift { lhs, rhs } => {
-                let l = self.evaluate_operand(&lhs);
-                let bits = self.evaluate_operand(&rhs);
-                l >> bits
-            }
-        };
-
-        self.wires.insert(wire.to_string(), val);
-        val
+        let gates = &self.gates;
+        self.signals
+            .get(symbol, |wire, signals| match &gates[wire.index()] {
+                Some(operation) => apply(operation, signals),
+                None => Ok(0),
+            })
+            .unwrap_or_else(|cycle| {
+                let names = cycle.0.iter().map(|&wire| self.wires.resolve(wire));
+                panic!("The circuit loops, cycle: {}", names.format(" -> "))
+            })
     }
+}
 
-    fn evaluate_operand(&mut self, operand: &Operand) -> u16 {
-        match operand {
-            Operand::Wire(w) => self.get_value(w),
-            Operand::Value(n) => *n,
-        }
-    }
+fn apply(
+    operation: &Operation<Symbol>,
+    signals: &mut Resolver<'_, Symbol, u16>,
+) -> Result<u16, Need<Symbol>> {
+    let mut value = |operand: &Operand<Symbol>| match operand {
+        Operand::Wire(wire) => signals.get(wire),
+        Operand::Value(n) => Ok(*n),
+    };
+    Ok(match operation {
+        Operation::Assign { operand } => value(operand)?,
+        Operation::And { lhs, rhs } => value(lhs)? & value(rhs)?,
+        Operation::Or { lhs, rhs } => value(lhs)? | value(rhs)?,
+        Operation::Not { rhs } => !value(rhs)?,
+        Operation::LShift { lhs, rhs } => value(lhs)? << value(rhs)?,
+        Operation::RShift { lhs, rhs } => value(lhs)? >> value(rhs)?,
+    })
 }
 
 #[aoc(day7, part1)]
@@ -213,6 +254,12 @@ mod tests {
         assert_eq!(solve_two(INPUT), 14134);
     }
 
+    #[test]
+    fn integration_all_inputs() {
+        input::check_inputs("day7", 1, solve_one);
+        input::check_inputs("day7", 2, solve_two);
+    }
+
     #[test]
     fn test_parse_assign_number() {
         let line = "123 -> x";
@@ -436,4 +483,23 @@ mod tests {
         assert_eq!(circuit.get_value("w"), 8);
         assert_eq!(circuit.get_value("p"), 65412);
     }
+
+    #[test]
+    fn test_missing_wire() {
+        let instructions = instructions!["x AND 10 -> w"];
+        let mut circuit = Circuit::new(instructions);
+
+        assert_eq!(circuit.get_value("w"), 0);
+        assert_eq!(circuit.get_value("nowhere"), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "The circuit loops, cycle:")]
+    fn test_loop() {
+        let instructions = instructions!["x -> y", "NOT y -> x", "1 -> z"];
+        let mut circuit = Circuit::new(instructions);
+
+        assert_eq!(circuit.get_value("z"), 1);
+        circuit.get_value("x");
+    }
 }
@@ -1,71 +1,19 @@
 use aoc_runner_derive::aoc;
 
+use crate::codec::{self, Dialect, EscapeError};
+
 /// A trait for counting the "in-memory" length of a string
 /// according to the Advent of Code Day 8 rules.
 pub trait MemoryCount {
     /// Returns the length of this string after interpreting
     /// the escape sequences. This assumes the string is
     /// surrounded by quotes (like `"abc"`).
-    fn memory_len(&self) -> usize;
+    fn memory_len(&self) -> Result<usize, EscapeError>;
 }
 
 impl MemoryCount for &str {
-    fn memory_len(&self) -> usize {
-        // If the line is shorter than 2, there's nothing to parse.
-        // Typically, AoC lines have at least two quotes.
-        if self.len() < 2 {
-            return 0;
-        }
-
-        // We'll skip the opening quote (index 0)
-        // and the closing quote (index self.len() - 1).
-        let mut i = 1;
-        let end = self.len() - 1;
-        let bytes = self.as_bytes();
-        let mut count = 0;
-
-        while i < end {
-            match bytes[i] {
-                b'\\' => {
-                    // We found a backslash. Let's look at the next characters.
-                    // If there's something like `\\`, `\"`, or `\x..`.
-                    if i + 1 < end {
-                        match bytes[i + 1] {
-                            b'\\' | b'"' => {
-                                // Examples: `\\` -> single backslash in memory
-                                //           `\"` -> single quote in memory
-                                count += 1;
-                                i += 2;
-                            }
-                            b'x' => {
-                                // Example: `\x27` means one character in memory,
-                                // skipping four bytes total: `\`, `x`, and 2 hex digits.
-                                count += 1;
-                                i += 4;
-                            }
-                            _ => {
-                                // Some unrecognized escape: we'll treat it as one character,
-                                // and skip two bytes (`\?`).
-                                count += 1;
-                                i += 2;
-                            }
-                        }
-                    } else {
-                        // There's a backslash at the end, but no next char.
-                        // We'll treat it as a single character to be safe.
-                        count += 1;
-                        i += 1;
-                    }
-                }
-                _ => {
-                    // A normal character
-                    count += 1;
-                    i += 1;
-                }
-            }
-        }
-
-        count
+    fn memory_len(&self) -> Result<usize, EscapeError> {
+        codec::decode(self, Dialect::AoC).map(|bytes| bytes.len())
     }
 }
 
@@ -98,20 +46,9 @@ where
     T: AsRef<str>,
 {
     fn encode(&self) -> String {
-        let s = self.as_ref();
-        let mut encoded = String::from("\"");
-
-        for c in s.chars() {
-            match c {
-                '\\' => {
-                    encoded.push_str("\\\\");
-                }
-                '"' => encoded.push_str("\\\""),
-                _ => encoded.push(c),
-            }
-        }
-        encoded.push('"');
-        encoded
+        let encoded = codec::encode(self.as_ref().as_bytes(), Dialect::AoC);
+        // Only ASCII escapes are added, so the UTF-8 stays valid.
+        String::from_utf8(encoded).expect("Encoding keeps UTF-8")
     }
 }
 
@@ -122,7 +59,9 @@ fn solve_one(input: &str) -> usize {
 
     for line in input.lines() {
         raw_len += line.len();
-        mem_len += line.memory_len();
+        mem_len += line
+            .memory_len()
+            .unwrap_or_else(|e| panic!("Invalid literal {}: {}", line, e));
     }
 
     raw_len - mem_len
@@ -181,14 +120,14 @@ mod tests {
     fn empty_quotes() {
         // The string is just `""`. That means length is 2 in raw,
         // but in memory it's 0 characters.
-        assert_eq!("\"\"".memory_len(), 0);
+        assert_eq!("\"\"".memory_len(), Ok(0));
     }
 
     #[test]
     fn normal_chars() {
         // The string is `"abc"` in raw form.
         // Memory should be 3.
-        assert_eq!("\"abc\"".memory_len(), 3);
+        assert_eq!("\"abc\"".memory_len(), Ok(3));
     }
 
     #[test]
@@ -197,7 +136,7 @@ mod tests {
         // After skipping outer quotes, we parse `aaa\"aaa`.
         // The `\"` becomes just one character `"`.
         // So the memory is 7 characters total.
-        assert_eq!("\"aaa\\\"aaa\"".memory_len(), 7);
+        assert_eq!("\"aaa\\\"aaa\"".memory_len(), Ok(7));
     }
 
     #[test]
@@ -205,26 +144,33 @@ mod tests {
         // The string is `"\x27"` in raw form, which is 4 chars + 2 quotes = 6 total.
         // In memory, `\x27` is a single character (ASCII 0x27, i.e. `'`).
         // So the memory length is 1.
-        assert_eq!("\"\\x27\"".memory_len(), 1);
+        assert_eq!("\"\\x27\"".memory_len(), Ok(1));
     }
 
     #[test]
     fn backslashes() {
-        // The string is `"\\\\\\"`, meaning raw: quote, backslash, backslash, backslash,
-        // backslash, backslash, quote. That's 7 in total.
-        // Let's break it down inside (skipping outer quotes): `\\\\\`
-        // We have 5 backslashes in a row inside.
-        //
-        // The first two `\\` -> 1 char in memory
-        // Next two `\\` -> another 1 char in memory
-        // The last one is just `\` alone (unrecognized escape?),
-        // but let's see how we handle it:
-        // Actually, since we always look ahead, we might treat them in pairs:
-        // - `\\` => 1
-        // - `\\` => 1
-        // - leftover `\` => 1
-        // total: 3
-        assert_eq!("\"\\\\\\\\\\\"".memory_len(), 3);
+        // The string is `"\\\\\"`, meaning raw: quote, five backslashes, quote.
+        // The first two pairs `\\` are one backslash each in memory, and the
+        // last backslash escapes the closing quote, so the literal never ends.
+        assert_eq!(
+            "\"\\\\\\\\\\\"".memory_len(),
+            Err(EscapeError::UnfinishedEscape { offset: 5 })
+        );
+    }
+
+    #[test]
+    fn invalid_hex_escape() {
+        // `\x` must be followed by two hex digits, not just any two bytes.
+        assert_eq!(
+            "\"a\\xzz\"".memory_len(),
+            Err(EscapeError::InvalidHex { offset: 2 })
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid literal \"\\q\": unknown escape `\\q` at byte 1")]
+    fn invalid_line() {
+        solve_one("\"\\q\"");
     }
 
     #[test]
@@ -241,7 +187,7 @@ mod tests {
         //   next is 'c' => count=4
         //   next is 'd' => count=5
         let line = "\"ab\\x20cd\"";
-        assert_eq!(line.memory_len(), 5);
+        assert_eq!(line.memory_len(), Ok(5));
     }
 
     #[test]
@@ -339,4 +285,10 @@ mod tests {
     fn integration_part_two() {
         assert_eq!(solve_two(INPUT), 2074);
     }
+
+    #[test]
+    fn integration_all_inputs() {
+        input::check_inputs("day8", 1, solve_one);
+        input::check_inputs("day8", 2, solve_two);
+    }
 }
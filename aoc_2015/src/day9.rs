@@ -1,19 +1,22 @@
-use std::{collections::HashMap, str::FromStr};
+use std::{
+    hash::{Hash, Hasher},
+    str::FromStr,
+};
 
 use aoc_runner_derive::aoc;
 use itertools::Itertools;
 use ndarray::Array2;
-use nom::{
-    bytes::complete::tag,
-    character::complete::{alpha1, digit1},
-    combinator::map_res,
-    error::VerboseError,
-    sequence::tuple,
+use nom::combinator::map;
+
+use crate::{
+    error::Error,
+    graph::Graph,
+    interner::{Interner, Symbol},
+    parse::{self, ParseResult},
+    search::{self, State},
+    trace,
 };
 
-#[derive(Debug, PartialEq, Eq)]
-struct ParserDistanceError;
-
 #[derive(Debug, PartialEq, Eq, Clone)]
 struct Distance {
     from: String,
@@ -21,139 +24,352 @@ struct Distance {
     distance: u32,
 }
 
-impl FromStr for Distance {
-    type Err = ParserDistanceError;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (_, (from, _, to, _, distance)) = tuple::<_, _, VerboseError<&str>, _>((
-            alpha1,
-            tag(" to "),
-            alpha1,
-            tag(" = "),
-            map_res(digit1, |s: &str| s.parse::<u32>()),
-        ))(s)
-        .map_err(|_| ParserDistanceError)?;
-
-        Ok(Distance {
+fn parse_distance(input: &str) -> ParseResult<'_, Distance> {
+    map(parse::route, |(from, to, distance): (&str, &str, u32)| {
+        Distance {
             from: from.to_string(),
             to: to.to_string(),
             distance,
-        })
+        }
+    })(input)
+}
+
+impl FromStr for Distance {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse::parse_all(s, parse_distance)
     }
 }
 
 impl From<&str> for Distance {
     fn from(s: &str) -> Self {
-        s.parse().unwrap()
+        s.parse().unwrap_or_else(|e| panic!("{}", e))
     }
 }
 
+#[derive(Debug, Clone, Copy)]
+enum Goal {
+    Shortest,
+    Longest,
+}
+
+impl Goal {
+    fn prefers(self, candidate: u32, current: u32) -> bool {
+        match self {
+            Goal::Shortest => candidate < current,
+            Goal::Longest => candidate > current,
+        }
+    }
+}
+
+/// The indices of the bits set in `mask`, lowest first.
+fn bits(mut mask: usize) -> impl Iterator<Item = usize> {
+    std::iter::from_fn(move || {
+        (mask != 0).then(|| {
+            let bit = mask.trailing_zeros() as usize;
+            mask &= mask - 1;
+            bit
+        })
+    })
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+struct Route<'a> {
+    cities: Vec<&'a str>,
+    distance: u32,
+}
+
+/// The cities are interned and added to the graph in the same order, so a
+/// city's symbol is also its index in the graph and in `distances`.
 struct DistanceMatrix {
-    distances: Array2<u32>,
-    city_index: HashMap<String, usize>,
+    names: Interner,
+    cities: Graph<Symbol, u32>,
+    distances: Array2<Option<u32>>,
 }
 
+/// The most cities [`DistanceMatrix::best_route`] takes: its tables need
+/// 100 MB for 20 cities, and twice as much for each city more.
+const MAX_CITIES: usize = 20;
+
 impl DistanceMatrix {
     fn new(distances: Vec<Distance>) -> Self {
-        let mut city_index = HashMap::new();
-        let mut index: usize = 0;
-        for Distance { from, to, .. } in &distances {
-            city_index.entry(from.clone()).or_insert_with(|| {
-                let current_index = index;
-                index += 1;
-                current_index
-            });
-            city_index.entry(to.clone()).or_insert_with(|| {
-                let current_index = index;
-                index += 1;
-                current_index
-            });
-        }
-
-        let n = city_index.len();
-        let mut distance_matrix = Array2::<u32>::from_elem((n, n), u32::MAX);
+        let mut names = Interner::new();
+        let roads = distances
+            .iter()
+            .map(|d| (names.intern(&d.from), names.intern(&d.to), d.distance))
+            .collect_vec();
 
-        for Distance { from, to, distance } in distances {
-            let from_idx = city_index[&from];
-            let to_idx = city_index[&to];
-            distance_matrix[[from_idx, to_idx]] = distance;
-            distance_matrix[[to_idx, from_idx]] = distance;
+        let mut cities = Graph::new();
+        for symbol in names.symbols() {
+            cities.add_node(symbol);
+        }
+        for (from, to, distance) in roads {
+            cities.add_undirected_edge(from, to, distance);
         }
 
         Self {
-            distances: distance_matrix,
-            city_index,
+            names,
+            distances: cities.adjacency_matrix(),
+            cities,
         }
     }
 
     #[allow(unused)]
     fn show_possible_routes(&self) {
-        let index_to_city: Vec<_> = self
-            .city_index
-            .iter()
-            .sorted_by_key(|&(_, &index)| index)
-            .map(|(city, _)| city.clone())
-            .collect();
-
-        for perm in index_to_city.iter().permutations(index_to_city.len()) {
-            let route = perm.into_iter().cloned().collect::<Vec<_>>();
+        for route in self.names.symbols().permutations(self.names.len()) {
             let distance = self.calculate_route_distance(&route);
-            let route_string = route.join(" -> ");
-            println!("{} = {}", route_string, distance);
+            let route_string = route.iter().map(|&c| self.names.resolve(c)).join(" -> ");
+            trace!("{} = {}", route_string, distance);
         }
     }
 
     fn get_shortest_route(&self) -> u32 {
-        let mut shortest_distance = u32::MAX;
-        for perm in self.city_index.keys().permutations(self.city_index.len()) {
-            let route = perm.into_iter().cloned().collect::<Vec<_>>();
-            let distance = self.calculate_route_distance(&route);
-            if distance < shortest_distance {
-                shortest_distance = distance;
-            }
-        }
-        shortest_distance
+        self.best_route(Goal::Shortest).distance
     }
 
     fn get_longest_route(&self) -> u32 {
-        let mut longest_distance = u32::MIN;
-        for perm in self.city_index.keys().permutations(self.city_index.len()) {
-            let route = perm.into_iter().cloned().collect::<Vec<_>>();
-            let distance = self.calculate_route_distance(&route);
-            if distance > longest_distance {
-                longest_distance = distance;
+        self.best_route(Goal::Longest).distance
+    }
+
+    /// Finds the best route visiting every city once with the Held-Karp
+    /// dynamic programming, in O(n²·2ⁿ) instead of the O(n!) of trying every
+    /// permutation.
+    ///
+    /// `best[mask][last]` is the best distance of a route through the cities
+    /// of `mask` ending at `last`, and `previous[mask][last]` the city before
+    /// `last` on it, to walk the route back.
+    ///
+    /// The tables take 5·n·2ⁿ bytes, so panics past [`MAX_CITIES`].
+    fn best_route(&self, goal: Goal) -> Route<'_> {
+        const NONE: u8 = u8::MAX;
+
+        let n = self.cities.len();
+        assert!(
+            n <= MAX_CITIES,
+            "Too many cities: {}, at most {} fit the tables",
+            n,
+            MAX_CITIES
+        );
+        let weights = self.weights();
+        if n == 0 {
+            return Route::default();
+        }
+
+        let full = (1usize << n) - 1;
+        let mut best = vec![0u32; (full + 1) * n];
+        let mut previous = vec![NONE; (full + 1) * n];
+
+        for mask in 1..=full {
+            for last in bits(mask) {
+                let rest = mask & !(1 << last);
+                if rest == 0 {
+                    continue;
+                }
+                let slot = mask * n + last;
+                for before in bits(rest) {
+                    let candidate = best[rest * n + before]
+                        .checked_add(weights[before * n + last])
+                        .unwrap_or_else(|| panic!("Route too long"));
+                    if previous[slot] == NONE || goal.prefers(candidate, best[slot]) {
+                        best[slot] = candidate;
+                        previous[slot] = before as u8;
+                    }
+                }
+            }
+        }
+
+        let (mut last, distance) = (0..n)
+            .map(|last| (last, best[full * n + last]))
+            .reduce(|a, b| if goal.prefers(b.1, a.1) { b } else { a })
+            .expect("There is a city");
+
+        let mut cities = Vec::with_capacity(n);
+        let mut mask = full;
+        loop {
+            cities.push(self.names.resolve(*self.cities.node(last)));
+            let before = previous[mask * n + last];
+            if before == NONE {
+                break;
             }
+            mask &= !(1 << last);
+            last = before as usize;
+        }
+        cities.reverse();
+
+        Route { cities, distance }
+    }
+
+    /// Finds the best distance with a depth-first branch and bound over
+    /// partial routes, as an alternative to [`DistanceMatrix::best_route`].
+    ///
+    /// Every route takes one road less than there are cities, so the longest
+    /// route is the shortest one once each distance is subtracted from the
+    /// longest road.
+    fn branch_and_bound(&self, goal: Goal) -> u32 {
+        let n = self.cities.len();
+        let weights = self.weights();
+        let Some(&longest_road) = weights.iter().max() else {
+            return 0;
+        };
+        let costs = match goal {
+            Goal::Shortest => weights,
+            Goal::Longest => weights.iter().map(|w| longest_road - w).collect(),
+        };
+        let costs = TourCosts::new(n, costs);
+
+        let start = Tour {
+            costs: &costs,
+            visited: 0,
+            last: None,
+        };
+        let report = search::branch_and_bound(start, |_, _| false);
+        let cost = report.solution.expect("There is a route").cost;
+        match goal {
+            Goal::Shortest => cost,
+            Goal::Longest => (n as u32 - 1) * longest_road - cost,
         }
-        longest_distance
     }
 
-    fn calculate_route_distance(&self, route: &[String]) -> u32 {
+    /// The distance between each pair of cities, at `from * n + to`.
+    ///
+    /// Like trying every permutation would, panics on a route between any two
+    /// cities without a distance, even where a complete route avoids it.
+    fn weights(&self) -> Vec<u32> {
+        self.distances
+            .indexed_iter()
+            .map(|((from, to), distance)| match distance {
+                Some(distance) => *distance,
+                None if from == to => 0,
+                None => panic!("Disconnected path"),
+            })
+            .collect_vec()
+    }
+
+    fn calculate_route_distance(&self, route: &[Symbol]) -> u32 {
         route
             .windows(2)
-            .map(|pair| {
-                let from_index = self.city_index[&pair[0]];
-                let to_index = self.city_index[&pair[1]];
-                self.distances[[from_index, to_index]]
-            })
-            .try_fold(0u32, |acc, distance| acc.checked_add(distance))
+            .map(|pair| self.distances[[pair[0].index(), pair[1].index()]])
+            .try_fold(0u32, |acc, distance| acc.checked_add(distance?))
             .unwrap_or_else(|| panic!("Disconnected path"))
     }
 }
 
+/// The costs of the roads between cities for a [`Tour`], with the cheapest
+/// road into each city.
+struct TourCosts {
+    n: usize,
+    costs: Vec<u32>,
+    cheapest_into: Vec<u32>,
+}
+
+impl TourCosts {
+    fn new(n: usize, costs: Vec<u32>) -> Self {
+        let cheapest_into = (0..n)
+            .map(|to| {
+                (0..n)
+                    .filter(|&from| from != to)
+                    .map(|from| costs[from * n + to])
+                    .min()
+                    .unwrap_or(0)
+            })
+            .collect();
+        Self {
+            n,
+            costs,
+            cheapest_into,
+        }
+    }
+}
+
+/// A partial route: the cities visited so far, and the last one. The costs
+/// are the same for every tour, and left out of comparisons.
+#[derive(Clone)]
+struct Tour<'a> {
+    costs: &'a TourCosts,
+    visited: usize,
+    last: Option<usize>,
+}
+
+impl PartialEq for Tour<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        (self.visited, self.last) == (other.visited, other.last)
+    }
+}
+
+impl Eq for Tour<'_> {}
+
+impl Hash for Tour<'_> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (self.visited, self.last).hash(state);
+    }
+}
+
+impl State for Tour<'_> {
+    type Cost = u32;
+
+    fn successors(&self) -> Vec<(Self, u32)> {
+        let n = self.costs.n;
+        bits(self.unvisited())
+            .map(|next| {
+                let tour = Tour {
+                    visited: self.visited | 1 << next,
+                    last: Some(next),
+                    ..*self
+                };
+                let cost = self
+                    .last
+                    .map_or(0, |last| self.costs.costs[last * n + next]);
+                (tour, cost)
+            })
+            .collect()
+    }
+
+    fn is_goal(&self) -> bool {
+        self.unvisited() == 0
+    }
+
+    /// Each city left is entered by a road at least as cheap as its cheapest
+    /// one, except the first one of the route, which is free.
+    fn heuristic(&self) -> u32 {
+        match self.last {
+            None => 0,
+            Some(_) => bits(self.unvisited())
+                .map(|city| self.costs.cheapest_into[city])
+                .sum(),
+        }
+    }
+}
+
+impl Tour<'_> {
+    fn unvisited(&self) -> usize {
+        ((1 << self.costs.n) - 1) & !self.visited
+    }
+}
+
+fn distance_matrix(input: &str) -> DistanceMatrix {
+    let distances = parse::parse_lines(input, parse_distance).unwrap_or_else(|e| panic!("{}", e));
+    DistanceMatrix::new(distances)
+}
+
 #[aoc(day9, part1)]
 fn solve_one(input: &str) -> u32 {
-    let distances = input.lines().map(Distance::from).collect();
-    let matrix = DistanceMatrix::new(distances);
+    distance_matrix(input).get_shortest_route()
+}
 
-    matrix.get_shortest_route()
+#[aoc(day9, part1, BranchAndBound)]
+fn solve_one_branch_and_bound(input: &str) -> u32 {
+    distance_matrix(input).branch_and_bound(Goal::Shortest)
 }
 
 #[aoc(day9, part2)]
 fn solve_two(input: &str) -> u32 {
-    let distances = input.lines().map(Distance::from).collect();
-    let matrix = DistanceMatrix::new(distances);
+    distance_matrix(input).get_longest_route()
+}
 
-    matrix.get_longest_route()
+#[aoc(day9, part2, BranchAndBound)]
+fn solve_two_branch_and_bound(input: &str) -> u32 {
+    distance_matrix(input).branch_and_bound(Goal::Longest)
 }
 
 #[cfg(test)]
@@ -244,6 +460,134 @@ C to D = 6"
         assert_eq!(solve_two(input), 0);
     }
 
+    #[test]
+    fn example_routes() {
+        let input = "London to Dublin = 464\nLondon to Belfast = 518\nDublin to Belfast = 141";
+        let matrix = DistanceMatrix::new(input.lines().map(Distance::from).collect());
+
+        let shortest = matrix.best_route(Goal::Shortest);
+        let longest = matrix.best_route(Goal::Longest);
+
+        assert_eq!(shortest.distance, 605);
+        assert!(
+            shortest.cities == ["London", "Dublin", "Belfast"]
+                || shortest.cities == ["Belfast", "Dublin", "London"]
+        );
+        assert_eq!(longest.distance, 982);
+        assert!(
+            longest.cities == ["Dublin", "London", "Belfast"]
+                || longest.cities == ["Belfast", "London", "Dublin"]
+        );
+    }
+
+    #[test]
+    fn matches_permutations() {
+        let input = "\
+A to B = 12
+A to C = 7
+A to D = 30
+A to E = 4
+B to C = 18
+B to D = 2
+B to E = 25
+C to D = 9
+C to E = 11
+D to E = 16";
+        let matrix = DistanceMatrix::new(input.lines().map(Distance::from).collect());
+        let distances = matrix
+            .names
+            .symbols()
+            .permutations(matrix.names.len())
+            .map(|route| matrix.calculate_route_distance(&route))
+            .collect_vec();
+
+        for goal in [Goal::Shortest, Goal::Longest] {
+            let route = matrix.best_route(goal);
+            let expected = match goal {
+                Goal::Shortest => distances.iter().min(),
+                Goal::Longest => distances.iter().max(),
+            };
+            assert_eq!(Some(&route.distance), expected);
+            assert_eq!(Some(&matrix.branch_and_bound(goal)), expected);
+            let cities = route
+                .cities
+                .iter()
+                .map(|c| matrix.names.get(c).unwrap())
+                .collect_vec();
+            assert_eq!(matrix.calculate_route_distance(&cities), route.distance);
+        }
+    }
+
+    #[test]
+    fn parse_error() {
+        let error = "London to Dublin = far".parse::<Distance>().unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 1, column 20: expected unsigned integer\nLondon to Dublin = far\n                   ^"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "line 2, column 1: expected identifier")]
+    fn invalid_line() {
+        solve_one("A to B = 1\n= 2");
+    }
+
+    #[test]
+    fn no_cities() {
+        assert_eq!(solve_one(""), 0);
+        assert_eq!(solve_two(""), 0);
+    }
+
+    #[test]
+    fn many_cities() {
+        // Cities on a line, so the shortest route walks it end to end and the
+        // longest one zigzags.
+        let n = 16;
+        let city = |i: u32| char::from_u32('A' as u32 + i).unwrap();
+        let input = (0..n)
+            .tuple_combinations()
+            .map(|(a, b)| format!("{} to {} = {}", city(a), city(b), b - a))
+            .join("\n");
+
+        assert_eq!(solve_one(&input), n - 1);
+        assert_eq!(solve_two(&input), n * n / 2 - 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Too many cities: 21, at most 20")]
+    fn too_many_cities() {
+        let city = |i: u32| char::from_u32('A' as u32 + i).unwrap();
+        let input = (0..21)
+            .tuple_combinations()
+            .map(|(a, b)| format!("{} to {} = 1", city(a), city(b)))
+            .join("\n");
+        solve_one(&input);
+    }
+
+    #[test]
+    #[should_panic(expected = "Route too long")]
+    fn route_too_long() {
+        solve_two("A to B = 4294967295\nB to C = 1\nA to C = 1");
+    }
+
+    #[test]
+    fn branch_and_bound_examples() {
+        let input = "London to Dublin = 464\nLondon to Belfast = 518\nDublin to Belfast = 141";
+        assert_eq!(solve_one_branch_and_bound(input), 605);
+        assert_eq!(solve_two_branch_and_bound(input), 982);
+
+        assert_eq!(solve_one_branch_and_bound("A to A = 0"), 0);
+        assert_eq!(solve_two_branch_and_bound("A to A = 0"), 0);
+        assert_eq!(solve_one_branch_and_bound(""), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Disconnected path")]
+    fn branch_and_bound_disconnected() {
+        solve_one_branch_and_bound("A to B = 5\nB to C = 6");
+    }
+
     const INPUT: &str = input!("day9");
 
     #[test]
@@ -255,4 +599,12 @@ C to D = 6"
     fn integration_part_two() {
         assert_eq!(solve_two(INPUT), 898);
     }
+
+    #[test]
+    fn integration_all_inputs() {
+        input::check_inputs("day9", 1, solve_one);
+        input::check_inputs("day9", 1, solve_one_branch_and_bound);
+        input::check_inputs("day9", 2, solve_two);
+        input::check_inputs("day9", 2, solve_two_branch_and_bound);
+    }
 }
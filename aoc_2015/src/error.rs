@@ -0,0 +1,38 @@
+use std::{io, path::PathBuf};
+
+use derive_more::derive::{Display, Error};
+
+/// Errors raised while loading puzzle inputs, their expected answers and other
+/// local files, or while parsing puzzle data.
+#[derive(Debug, Display, Error)]
+pub enum Error {
+    #[display("failed to read {}: {source}", path.display())]
+    Io { path: PathBuf, source: io::Error },
+    #[display("{}:{line}: invalid answer entry `{entry}`", path.display())]
+    InvalidAnswer {
+        path: PathBuf,
+        line: usize,
+        #[error(not(source))]
+        entry: String,
+    },
+    #[display("invalid leaderboard {}: {source}", path.display())]
+    InvalidLeaderboard {
+        path: PathBuf,
+        source: serde_json::Error,
+    },
+    #[display("line {line}, column {column}: {message}\n{snippet}")]
+    Parse {
+        line: usize,
+        column: usize,
+        message: String,
+        snippet: String,
+    },
+    #[display("line {line} has {found} cells, expected {expected}")]
+    RaggedGrid {
+        line: usize,
+        expected: usize,
+        found: usize,
+    },
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
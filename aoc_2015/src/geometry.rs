@@ -0,0 +1,527 @@
+use std::{fmt, str::FromStr};
+
+use derive_more::derive::{Add, AddAssign, Display, Mul, Neg, Sub, SubAssign};
+
+/// A point or offset on a plane, with `y` growing downwards as in [`Grid`].
+///
+/// [`Grid`]: crate::grid::Grid
+///
+/// # Examples
+///
+/// ```rust
+/// use aoc_2015::geometry::{Direction, Vec2};
+///
+/// let mut position = Vec2::ORIGIN;
+/// position += Direction::Right.offset() * 3;
+/// position += Direction::Up.offset();
+///
+/// assert_eq!(position, Vec2::new(3, -1));
+/// assert_eq!(position.manhattan(Vec2::ORIGIN), 4);
+/// ```
+#[derive(
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Clone,
+    Copy,
+    Hash,
+    Display,
+    Add,
+    Sub,
+    AddAssign,
+    SubAssign,
+    Neg,
+    Mul,
+)]
+#[display("({x}, {y})")]
+pub struct Vec2 {
+    pub x: i64,
+    pub y: i64,
+}
+
+impl Vec2 {
+    pub const ORIGIN: Self = Self::new(0, 0);
+
+    pub const fn new(x: i64, y: i64) -> Self {
+        Self { x, y }
+    }
+
+    pub fn manhattan(self, other: Self) -> i64 {
+        (self.x - other.x).abs() + (self.y - other.y).abs()
+    }
+
+    pub fn chebyshev(self, other: Self) -> i64 {
+        (self.x - other.x).abs().max((self.y - other.y).abs())
+    }
+
+    /// The point turned a quarter clockwise around the origin, `turns` times.
+    /// Negative turns go counterclockwise.
+    pub fn rotate(self, turns: i32) -> Self {
+        match turns.rem_euclid(4) {
+            0 => self,
+            1 => Self::new(-self.y, self.x),
+            2 => Self::new(-self.x, -self.y),
+            _ => Self::new(self.y, -self.x),
+        }
+    }
+}
+
+impl From<(i64, i64)> for Vec2 {
+    fn from((x, y): (i64, i64)) -> Self {
+        Self::new(x, y)
+    }
+}
+
+/// A point or offset in space.
+#[derive(
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Clone,
+    Copy,
+    Hash,
+    Display,
+    Add,
+    Sub,
+    AddAssign,
+    SubAssign,
+    Neg,
+    Mul,
+)]
+#[display("({x}, {y}, {z})")]
+pub struct Vec3 {
+    pub x: i64,
+    pub y: i64,
+    pub z: i64,
+}
+
+impl Vec3 {
+    pub const ORIGIN: Self = Self::new(0, 0, 0);
+
+    pub const fn new(x: i64, y: i64, z: i64) -> Self {
+        Self { x, y, z }
+    }
+
+    pub fn manhattan(self, other: Self) -> i64 {
+        (self.x - other.x).abs() + (self.y - other.y).abs() + (self.z - other.z).abs()
+    }
+
+    pub fn chebyshev(self, other: Self) -> i64 {
+        (self.x - other.x)
+            .abs()
+            .max((self.y - other.y).abs())
+            .max((self.z - other.z).abs())
+    }
+}
+
+impl From<(i64, i64, i64)> for Vec3 {
+    fn from((x, y, z): (i64, i64, i64)) -> Self {
+        Self::new(x, y, z)
+    }
+}
+
+/// One of the four orthogonal directions.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum Direction {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Direction {
+    /// Every direction, clockwise from `Up`.
+    pub const ALL: [Self; 4] = [Self::Up, Self::Right, Self::Down, Self::Left];
+
+    pub fn offset(self) -> Vec2 {
+        match self {
+            Self::Up => Vec2::new(0, -1),
+            Self::Right => Vec2::new(1, 0),
+            Self::Down => Vec2::new(0, 1),
+            Self::Left => Vec2::new(-1, 0),
+        }
+    }
+
+    /// The direction turned a quarter clockwise, `turns` times. Negative turns
+    /// go counterclockwise.
+    pub fn rotate(self, turns: i32) -> Self {
+        Self::ALL[(self as i32 + turns).rem_euclid(4) as usize]
+    }
+
+    pub fn turn_right(self) -> Self {
+        self.rotate(1)
+    }
+
+    pub fn turn_left(self) -> Self {
+        self.rotate(-1)
+    }
+
+    pub fn reverse(self) -> Self {
+        self.rotate(2)
+    }
+}
+
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", Alphabet::ARROWS.symbol(*self))
+    }
+}
+
+/// Parses the arrows written by `Display`: `^`, `v`, `<` and `>`.
+impl From<char> for Direction {
+    fn from(value: char) -> Self {
+        Alphabet::ARROWS
+            .parse(value)
+            .unwrap_or_else(|| panic!("This is not a valid direction: {}", value))
+    }
+}
+
+/// The characters a puzzle uses for the four directions.
+///
+/// # Examples
+///
+/// ```rust
+/// use aoc_2015::geometry::{Alphabet, Direction};
+///
+/// assert_eq!(Alphabet::UDLR.parse('L'), Some(Direction::Left));
+/// assert_eq!(Alphabet::NSEW.symbol(Direction::Up), 'N');
+/// assert_eq!(Alphabet::ARROWS.parse('x'), None);
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Alphabet {
+    up: char,
+    right: char,
+    down: char,
+    left: char,
+}
+
+impl Alphabet {
+    pub const ARROWS: Self = Self::new('^', '>', 'v', '<');
+    pub const UDLR: Self = Self::new('U', 'R', 'D', 'L');
+    pub const NSEW: Self = Self::new('N', 'E', 'S', 'W');
+
+    /// An alphabet from the characters of `Up`, `Right`, `Down` and `Left`.
+    ///
+    /// # Panics
+    ///
+    /// If two directions share a character, as parsing could not round-trip.
+    pub const fn new(up: char, right: char, down: char, left: char) -> Self {
+        assert!(
+            up != right
+                && up != down
+                && up != left
+                && right != down
+                && right != left
+                && down != left,
+            "directions must have distinct characters"
+        );
+        Self {
+            up,
+            right,
+            down,
+            left,
+        }
+    }
+
+    pub fn parse(&self, ch: char) -> Option<Direction> {
+        Direction::ALL.into_iter().find(|&d| self.symbol(d) == ch)
+    }
+
+    pub fn symbol(&self, direction: Direction) -> char {
+        match direction {
+            Direction::Up => self.up,
+            Direction::Right => self.right,
+            Direction::Down => self.down,
+            Direction::Left => self.left,
+        }
+    }
+}
+
+/// One of the eight compass directions, orthogonal and diagonal.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum Direction8 {
+    N,
+    NE,
+    E,
+    SE,
+    S,
+    SW,
+    W,
+    NW,
+}
+
+impl Direction8 {
+    /// Every direction, clockwise from `N`.
+    pub const ALL: [Self; 8] = [
+        Self::N,
+        Self::NE,
+        Self::E,
+        Self::SE,
+        Self::S,
+        Self::SW,
+        Self::W,
+        Self::NW,
+    ];
+
+    pub fn offset(self) -> Vec2 {
+        match self {
+            Self::N => Vec2::new(0, -1),
+            Self::NE => Vec2::new(1, -1),
+            Self::E => Vec2::new(1, 0),
+            Self::SE => Vec2::new(1, 1),
+            Self::S => Vec2::new(0, 1),
+            Self::SW => Vec2::new(-1, 1),
+            Self::W => Vec2::new(-1, 0),
+            Self::NW => Vec2::new(-1, -1),
+        }
+    }
+
+    /// The direction turned an eighth clockwise, `turns` times. Negative turns
+    /// go counterclockwise.
+    pub fn rotate(self, turns: i32) -> Self {
+        Self::ALL[(self as i32 + turns).rem_euclid(8) as usize]
+    }
+
+    pub fn turn_right(self) -> Self {
+        self.rotate(2)
+    }
+
+    pub fn turn_left(self) -> Self {
+        self.rotate(-2)
+    }
+
+    pub fn reverse(self) -> Self {
+        self.rotate(4)
+    }
+}
+
+impl From<Direction> for Direction8 {
+    fn from(direction: Direction) -> Self {
+        match direction {
+            Direction::Up => Self::N,
+            Direction::Right => Self::E,
+            Direction::Down => Self::S,
+            Direction::Left => Self::W,
+        }
+    }
+}
+
+/// One of the six directions of a hex grid with flat-topped cells, moving
+/// across cube coordinates where `x + y + z == 0`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum HexDirection {
+    N,
+    NE,
+    SE,
+    S,
+    SW,
+    NW,
+}
+
+impl HexDirection {
+    /// Every direction, clockwise from `N`.
+    pub const ALL: [Self; 6] = [Self::N, Self::NE, Self::SE, Self::S, Self::SW, Self::NW];
+
+    pub fn offset(self) -> Vec3 {
+        match self {
+            Self::N => Vec3::new(0, 1, -1),
+            Self::NE => Vec3::new(1, 0, -1),
+            Self::SE => Vec3::new(1, -1, 0),
+            Self::S => Vec3::new(0, -1, 1),
+            Self::SW => Vec3::new(-1, 0, 1),
+            Self::NW => Vec3::new(-1, 1, 0),
+        }
+    }
+
+    /// The direction turned a sixth clockwise, `turns` times. Negative turns
+    /// go counterclockwise.
+    pub fn rotate(self, turns: i32) -> Self {
+        Self::ALL[(self as i32 + turns).rem_euclid(6) as usize]
+    }
+
+    pub fn reverse(self) -> Self {
+        self.rotate(3)
+    }
+
+    /// The number of steps between two cells in cube coordinates.
+    pub fn distance(from: Vec3, to: Vec3) -> i64 {
+        from.manhattan(to) / 2
+    }
+}
+
+impl fmt::Display for HexDirection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::N => "n",
+            Self::NE => "ne",
+            Self::SE => "se",
+            Self::S => "s",
+            Self::SW => "sw",
+            Self::NW => "nw",
+        };
+        f.write_str(name)
+    }
+}
+
+impl FromStr for HexDirection {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|d| d.to_string() == s)
+            .ok_or_else(|| format!("This is not a valid hex direction: {}", s))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::*;
+
+    #[test]
+    fn vector_arithmetic() {
+        let a = Vec2::new(1, 2);
+        let b = Vec2::new(-3, 5);
+
+        assert_eq!(a + b, Vec2::new(-2, 7));
+        assert_eq!(a - b, Vec2::new(4, -3));
+        assert_eq!(-a, Vec2::new(-1, -2));
+        assert_eq!(a * 3, Vec2::new(3, 6));
+
+        let mut c = a;
+        c += b;
+        c -= a;
+        assert_eq!(c, b);
+
+        assert_eq!(
+            Vec3::new(1, 2, 3) + Vec3::new(1, 1, 1) * 2,
+            Vec3::new(3, 4, 5)
+        );
+    }
+
+    #[test]
+    fn distances() {
+        let a = Vec2::new(1, 2);
+        let b = Vec2::new(-3, 5);
+
+        assert_eq!(a.manhattan(b), 7);
+        assert_eq!(a.chebyshev(b), 4);
+        assert_eq!(Vec3::new(1, 2, 3).manhattan(Vec3::ORIGIN), 6);
+        assert_eq!(Vec3::new(1, -5, 3).chebyshev(Vec3::ORIGIN), 5);
+    }
+
+    #[test]
+    fn rotate_vectors() {
+        let v = Vec2::new(2, 1);
+
+        assert_eq!(v.rotate(1), Vec2::new(-1, 2));
+        assert_eq!(v.rotate(-1), Vec2::new(1, -2));
+        assert_eq!(v.rotate(2), -v);
+        assert_eq!(v.rotate(4), v);
+        // A quarter turn of a vector matches a quarter turn of a direction.
+        for d in Direction::ALL {
+            assert_eq!(d.offset().rotate(1), d.turn_right().offset());
+        }
+    }
+
+    #[rstest]
+    #[case(Direction::Up, Direction::Right, Direction::Left, Direction::Down)]
+    #[case(Direction::Right, Direction::Down, Direction::Up, Direction::Left)]
+    #[case(Direction::Down, Direction::Left, Direction::Right, Direction::Up)]
+    #[case(Direction::Left, Direction::Up, Direction::Down, Direction::Right)]
+    fn turn_directions(
+        #[case] direction: Direction,
+        #[case] right: Direction,
+        #[case] left: Direction,
+        #[case] reverse: Direction,
+    ) {
+        assert_eq!(direction.turn_right(), right);
+        assert_eq!(direction.turn_left(), left);
+        assert_eq!(direction.reverse(), reverse);
+        assert_eq!(direction.rotate(-7), right);
+        assert_eq!(direction.offset() + reverse.offset(), Vec2::ORIGIN);
+    }
+
+    #[test]
+    fn display_from_char_round_trip() {
+        for d in Direction::ALL {
+            let ch = d.to_string().chars().next().unwrap();
+            assert_eq!(Direction::from(ch), d);
+        }
+        assert_eq!(Direction::ALL.map(|d| d.to_string()).concat(), "^>v<");
+    }
+
+    #[rstest]
+    #[case(Alphabet::ARROWS, "^>v<")]
+    #[case(Alphabet::UDLR, "URDL")]
+    #[case(Alphabet::NSEW, "NESW")]
+    fn alphabets_round_trip(#[case] alphabet: Alphabet, #[case] symbols: &str) {
+        for (d, ch) in Direction::ALL.into_iter().zip(symbols.chars()) {
+            assert_eq!(alphabet.symbol(d), ch);
+            assert_eq!(alphabet.parse(ch), Some(d));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "directions must have distinct characters")]
+    fn ambiguous_alphabet() {
+        let _ = Alphabet::new('a', 'b', 'a', 'c');
+    }
+
+    #[test]
+    #[should_panic(expected = "This is not a valid direction: U")]
+    fn invalid_char() {
+        let _ = Direction::from('U');
+    }
+
+    #[test]
+    fn eight_directions() {
+        assert_eq!(Direction8::N.turn_right(), Direction8::E);
+        assert_eq!(Direction8::N.rotate(1), Direction8::NE);
+        assert_eq!(Direction8::NW.rotate(1), Direction8::N);
+        assert_eq!(Direction8::SW.turn_left(), Direction8::SE);
+        assert_eq!(Direction8::NE.reverse(), Direction8::SW);
+        for d in Direction8::ALL {
+            assert_eq!(d.offset().chebyshev(Vec2::ORIGIN), 1);
+            assert_eq!(d.offset() + d.reverse().offset(), Vec2::ORIGIN);
+        }
+        for d in Direction::ALL {
+            assert_eq!(Direction8::from(d).offset(), d.offset());
+        }
+    }
+
+    #[test]
+    fn hex_directions() {
+        for d in HexDirection::ALL {
+            let offset = d.offset();
+            assert_eq!(offset.x + offset.y + offset.z, 0);
+            assert_eq!(HexDirection::distance(Vec3::ORIGIN, offset), 1);
+            assert_eq!(d.to_string().parse::<HexDirection>(), Ok(d));
+            assert_eq!(offset + d.reverse().offset(), Vec3::ORIGIN);
+        }
+
+        // 2017 day 11 examples.
+        let walk = |path: &str| {
+            path.split(',')
+                .map(|d| d.parse::<HexDirection>().unwrap().offset())
+                .fold(Vec3::ORIGIN, |a, b| a + b)
+        };
+        assert_eq!(HexDirection::distance(Vec3::ORIGIN, walk("ne,ne,ne")), 3);
+        assert_eq!(HexDirection::distance(Vec3::ORIGIN, walk("ne,ne,sw,sw")), 0);
+        assert_eq!(HexDirection::distance(Vec3::ORIGIN, walk("ne,ne,s,s")), 2);
+        assert_eq!(
+            HexDirection::distance(Vec3::ORIGIN, walk("se,sw,se,sw,sw")),
+            3
+        );
+        assert!("up".parse::<HexDirection>().is_err());
+    }
+}
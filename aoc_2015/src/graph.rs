@@ -0,0 +1,585 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, VecDeque},
+    fmt,
+    hash::Hash,
+    ops::Add,
+};
+
+use ndarray::Array2;
+
+/// A directed graph stored as adjacency lists, with nodes identified by any
+/// hashable value and edges carrying a weight (`()` when they have none).
+///
+/// Nodes are numbered in insertion order, which is also the order every
+/// traversal breaks ties in.
+///
+/// # Examples
+///
+/// ```rust
+/// use aoc_2015::graph::Graph;
+///
+/// let mut graph = Graph::new();
+/// graph.add_undirected_edge("London", "Dublin", 464);
+/// graph.add_undirected_edge("London", "Belfast", 518);
+/// graph.add_undirected_edge("Dublin", "Belfast", 141);
+///
+/// let path = graph.dijkstra(&"London", &"Belfast").unwrap();
+/// assert_eq!(path.nodes, vec![&"London", &"Belfast"]);
+/// assert_eq!(path.cost, 518);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Graph<N, W = ()> {
+    nodes: Vec<N>,
+    index: HashMap<N, usize>,
+    edges: Vec<Vec<(usize, W)>>,
+}
+
+/// A path found by [`Graph::dijkstra`] or [`Graph::astar`], from the start to
+/// the goal inclusive.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Path<'a, N, W> {
+    pub nodes: Vec<&'a N>,
+    pub cost: W,
+}
+
+/// The error of [`Graph::toposort`]: one of the cycles of the graph, listing
+/// each of its nodes once in edge order.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Cycle<'a, N>(pub Vec<&'a N>);
+
+impl<N: fmt::Display> fmt::Display for Cycle<'_, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "cycle: ")?;
+        for node in &self.0 {
+            write!(f, "{} -> ", node)?;
+        }
+        match self.0.first() {
+            Some(first) => write!(f, "{}", first),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<N, W> Default for Graph<N, W> {
+    fn default() -> Self {
+        Self {
+            nodes: Vec::new(),
+            index: HashMap::new(),
+            edges: Vec::new(),
+        }
+    }
+}
+
+impl<N: Eq + Hash + Clone, W> Graph<N, W> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `node` if it is new, and returns its index either way.
+    pub fn add_node(&mut self, node: N) -> usize {
+        if let Some(&index) = self.index.get(&node) {
+            return index;
+        }
+        let index = self.nodes.len();
+        self.index.insert(node.clone(), index);
+        self.nodes.push(node);
+        self.edges.push(Vec::new());
+        index
+    }
+
+    /// Adds an edge from `from` to `to`, adding the nodes as needed.
+    pub fn add_edge(&mut self, from: N, to: N, weight: W) {
+        let from = self.add_node(from);
+        let to = self.add_node(to);
+        self.edges[from].push((to, weight));
+    }
+
+    /// Adds an edge each way between `a` and `b`.
+    pub fn add_undirected_edge(&mut self, a: N, b: N, weight: W)
+    where
+        W: Clone,
+    {
+        self.add_edge(a.clone(), b.clone(), weight.clone());
+        if a != b {
+            self.add_edge(b, a, weight);
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    pub fn index_of(&self, node: &N) -> Option<usize> {
+        self.index.get(node).copied()
+    }
+
+    pub fn node(&self, index: usize) -> &N {
+        &self.nodes[index]
+    }
+
+    /// Every node, in insertion order.
+    pub fn nodes(&self) -> &[N] {
+        &self.nodes
+    }
+
+    /// The nodes `node` has an edge to, with the weights of those edges.
+    pub fn neighbours(&self, node: &N) -> impl Iterator<Item = (&N, &W)> {
+        self.index_of(node)
+            .map(|index| self.edges[index].as_slice())
+            .unwrap_or_default()
+            .iter()
+            .map(|(to, weight)| (&self.nodes[*to], weight))
+    }
+
+    /// The graph as a matrix indexed by node indices, holding the weight of
+    /// the edge from the row's node to the column's one, if any. With parallel
+    /// edges, the last one added wins.
+    pub fn adjacency_matrix(&self) -> Array2<Option<W>>
+    where
+        W: Clone,
+    {
+        let mut matrix = Array2::from_elem((self.len(), self.len()), None);
+        for (from, edges) in self.edges.iter().enumerate() {
+            for (to, weight) in edges {
+                matrix[[from, *to]] = Some(weight.clone());
+            }
+        }
+        matrix
+    }
+
+    /// The nodes reachable from `start` in breadth-first order, `start`
+    /// first.
+    pub fn bfs(&self, start: &N) -> Vec<&N> {
+        let Some(start) = self.index_of(start) else {
+            return Vec::new();
+        };
+        let mut seen = vec![false; self.len()];
+        let mut queue = VecDeque::from([start]);
+        let mut order = Vec::new();
+        seen[start] = true;
+
+        while let Some(current) = queue.pop_front() {
+            order.push(&self.nodes[current]);
+            for &(next, _) in &self.edges[current] {
+                if !seen[next] {
+                    seen[next] = true;
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        order
+    }
+
+    /// The nodes reachable from `start` in depth-first preorder, `start`
+    /// first.
+    pub fn dfs(&self, start: &N) -> Vec<&N> {
+        let Some(start) = self.index_of(start) else {
+            return Vec::new();
+        };
+        let mut seen = vec![false; self.len()];
+        let mut stack = vec![start];
+        let mut order = Vec::new();
+
+        while let Some(current) = stack.pop() {
+            if seen[current] {
+                continue;
+            }
+            seen[current] = true;
+            order.push(&self.nodes[current]);
+            // Pushed in reverse so that the first edge is explored first.
+            for &(next, _) in self.edges[current].iter().rev() {
+                if !seen[next] {
+                    stack.push(next);
+                }
+            }
+        }
+
+        order
+    }
+
+    /// Orders the nodes so that every edge goes forward, or returns one of the
+    /// cycles preventing it.
+    pub fn toposort(&self) -> Result<Vec<&N>, Cycle<'_, N>> {
+        let mut in_degree = vec![0usize; self.len()];
+        for edges in &self.edges {
+            for &(to, _) in edges {
+                in_degree[to] += 1;
+            }
+        }
+
+        let mut queue: VecDeque<_> = (0..self.len()).filter(|&i| in_degree[i] == 0).collect();
+        let mut order = Vec::with_capacity(self.len());
+        while let Some(current) = queue.pop_front() {
+            order.push(current);
+            for &(next, _) in &self.edges[current] {
+                in_degree[next] -= 1;
+                if in_degree[next] == 0 {
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        if order.len() == self.len() {
+            Ok(order.into_iter().map(|i| &self.nodes[i]).collect())
+        } else {
+            Err(self.find_cycle(&in_degree))
+        }
+    }
+
+    /// Every node left with incoming edges once Kahn's algorithm stalls has a
+    /// predecessor in the same situation, so walking predecessors must loop.
+    fn find_cycle(&self, in_degree: &[usize]) -> Cycle<'_, N> {
+        let mut predecessor = vec![None; self.len()];
+        for (from, edges) in self.edges.iter().enumerate() {
+            for &(to, _) in edges {
+                if in_degree[from] > 0 && in_degree[to] > 0 {
+                    predecessor[to].get_or_insert(from);
+                }
+            }
+        }
+
+        let mut position = vec![None; self.len()];
+        let mut walk = Vec::new();
+        let mut current = (0..self.len())
+            .find(|&i| in_degree[i] > 0)
+            .expect("a stalled sort leaves nodes behind");
+        while position[current].is_none() {
+            position[current] = Some(walk.len());
+            walk.push(current);
+            current = predecessor[current].expect("a stalled node has a stalled predecessor");
+        }
+
+        // The walk went backwards, and ends just before reaching `current`.
+        let start = position[current].unwrap();
+        let mut cycle: Vec<_> = walk[start..]
+            .iter()
+            .rev()
+            .map(|&i| &self.nodes[i])
+            .collect();
+        cycle.rotate_right(1);
+        Cycle(cycle)
+    }
+
+    /// The strongly connected components, found with Tarjan's algorithm.
+    ///
+    /// A component comes before every component it has an edge to, so the
+    /// result is a topological order of the condensed graph.
+    pub fn strongly_connected_components(&self) -> Vec<Vec<&N>> {
+        const UNVISITED: usize = usize::MAX;
+
+        let n = self.len();
+        let mut index = vec![UNVISITED; n];
+        let mut low_link = vec![0; n];
+        let mut on_stack = vec![false; n];
+        let mut stack = Vec::new();
+        let mut components = Vec::new();
+        let mut next_index = 0;
+
+        for root in 0..n {
+            if index[root] != UNVISITED {
+                continue;
+            }
+
+            // Each frame is a node and how many of its edges were explored.
+            let mut calls = vec![(root, 0)];
+            index[root] = next_index;
+            low_link[root] = next_index;
+            next_index += 1;
+            stack.push(root);
+            on_stack[root] = true;
+
+            while let Some(&mut (node, ref mut edge)) = calls.last_mut() {
+                if let Some(&(next, _)) = self.edges[node].get(*edge) {
+                    *edge += 1;
+                    if index[next] == UNVISITED {
+                        index[next] = next_index;
+                        low_link[next] = next_index;
+                        next_index += 1;
+                        stack.push(next);
+                        on_stack[next] = true;
+                        calls.push((next, 0));
+                    } else if on_stack[next] {
+                        low_link[node] = low_link[node].min(index[next]);
+                    }
+                    continue;
+                }
+
+                calls.pop();
+                if let Some(&(parent, _)) = calls.last() {
+                    low_link[parent] = low_link[parent].min(low_link[node]);
+                }
+                if low_link[node] == index[node] {
+                    let mut component = Vec::new();
+                    loop {
+                        let member = stack.pop().unwrap();
+                        on_stack[member] = false;
+                        component.push(&self.nodes[member]);
+                        if member == node {
+                            break;
+                        }
+                    }
+                    component.reverse();
+                    components.push(component);
+                }
+            }
+        }
+
+        components.reverse();
+        components
+    }
+}
+
+impl<N, W> Graph<N, W>
+where
+    N: Eq + Hash + Clone,
+    W: Copy + Ord + Default + Add<Output = W>,
+{
+    /// The cheapest path from `start` to `goal`, with `W::default()` as the
+    /// cost of an empty path.
+    pub fn dijkstra(&self, start: &N, goal: &N) -> Option<Path<'_, N, W>> {
+        self.astar(start, goal, |_| W::default())
+    }
+
+    /// The cheapest path from `start` to `goal`, exploring first the nodes
+    /// whose `heuristic` promises the cheapest total. The heuristic must never
+    /// overestimate the remaining cost for the path to be the cheapest.
+    pub fn astar(
+        &self,
+        start: &N,
+        goal: &N,
+        heuristic: impl Fn(&N) -> W,
+    ) -> Option<Path<'_, N, W>> {
+        let start = self.index_of(start)?;
+        let goal = self.index_of(goal)?;
+
+        let mut cost: Vec<Option<W>> = vec![None; self.len()];
+        let mut previous = vec![None; self.len()];
+        let mut heap = BinaryHeap::new();
+        cost[start] = Some(W::default());
+        heap.push(Reverse((heuristic(&self.nodes[start]), start)));
+
+        while let Some(Reverse((_, current))) = heap.pop() {
+            let current_cost = cost[current].unwrap();
+            if current == goal {
+                let mut nodes = vec![&self.nodes[goal]];
+                let mut node = goal;
+                while let Some(before) = previous[node] {
+                    nodes.push(&self.nodes[before]);
+                    node = before;
+                }
+                nodes.reverse();
+                return Some(Path {
+                    nodes,
+                    cost: current_cost,
+                });
+            }
+
+            for &(next, weight) in &self.edges[current] {
+                let next_cost = current_cost + weight;
+                if cost[next].is_none_or(|known| next_cost < known) {
+                    cost[next] = Some(next_cost);
+                    previous[next] = Some(current);
+                    heap.push(Reverse((next_cost + heuristic(&self.nodes[next]), next)));
+                }
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn graph(edges: &[(&'static str, &'static str)]) -> Graph<&'static str> {
+        let mut graph = Graph::new();
+        for &(from, to) in edges {
+            graph.add_edge(from, to, ());
+        }
+        graph
+    }
+
+    #[test]
+    fn nodes_and_neighbours() {
+        let mut graph = Graph::new();
+        assert!(graph.is_empty());
+
+        graph.add_undirected_edge('a', 'b', 3);
+        graph.add_edge('b', 'c', 4);
+        assert_eq!(graph.add_node('d'), 3);
+        assert_eq!(graph.add_node('a'), 0);
+
+        assert_eq!(graph.len(), 4);
+        assert_eq!(graph.nodes(), &['a', 'b', 'c', 'd']);
+        assert_eq!(graph.index_of(&'c'), Some(2));
+        assert_eq!(graph.node(1), &'b');
+        assert_eq!(
+            graph.neighbours(&'b').collect::<Vec<_>>(),
+            vec![(&'a', &3), (&'c', &4)]
+        );
+        assert_eq!(graph.neighbours(&'z').count(), 0);
+    }
+
+    #[test]
+    fn adjacency_matrix() {
+        let mut graph = Graph::new();
+        graph.add_undirected_edge(1, 2, 5);
+        graph.add_edge(2, 3, 7);
+        graph.add_undirected_edge(3, 3, 0);
+
+        let matrix = graph.adjacency_matrix();
+        assert_eq!(
+            matrix,
+            ndarray::arr2(&[
+                [None, Some(5), None],
+                [Some(5), None, Some(7)],
+                [None, None, Some(0)],
+            ])
+        );
+    }
+
+    #[test]
+    fn traversals() {
+        let graph = graph(&[
+            ("a", "b"),
+            ("a", "c"),
+            ("b", "d"),
+            ("c", "d"),
+            ("d", "a"),
+            ("e", "a"),
+        ]);
+
+        assert_eq!(graph.bfs(&"a"), vec![&"a", &"b", &"c", &"d"]);
+        assert_eq!(graph.dfs(&"a"), vec![&"a", &"b", &"d", &"c"]);
+        assert_eq!(graph.bfs(&"e").len(), 5);
+        assert_eq!(graph.dfs(&"z"), Vec::<&&str>::new());
+    }
+
+    #[test]
+    fn dijkstra() {
+        let mut graph = Graph::new();
+        graph.add_edge('a', 'b', 7);
+        graph.add_edge('a', 'c', 9);
+        graph.add_edge('a', 'f', 14);
+        graph.add_edge('b', 'c', 10);
+        graph.add_edge('b', 'd', 15);
+        graph.add_edge('c', 'd', 11);
+        graph.add_edge('c', 'f', 2);
+        graph.add_edge('d', 'e', 6);
+        graph.add_edge('f', 'e', 9);
+
+        let path = graph.dijkstra(&'a', &'e').unwrap();
+        assert_eq!(path.nodes, vec![&'a', &'c', &'f', &'e']);
+        assert_eq!(path.cost, 20);
+
+        assert_eq!(graph.dijkstra(&'e', &'a'), None);
+        assert_eq!(
+            graph.dijkstra(&'a', &'a'),
+            Some(Path {
+                nodes: vec![&'a'],
+                cost: 0
+            })
+        );
+    }
+
+    #[test]
+    fn astar_on_a_grid() {
+        use crate::geometry::{Direction, Vec2};
+
+        // A 5x5 open grid with a wall along x == 2, except at y == 4.
+        let mut graph = Graph::new();
+        for x in 0..5 {
+            for y in 0..5 {
+                let from = Vec2::new(x, y);
+                for d in Direction::ALL {
+                    let to = from + d.offset();
+                    let wall = |p: Vec2| p.x == 2 && p.y != 4;
+                    if (0..5).contains(&to.x) && (0..5).contains(&to.y) && !wall(from) && !wall(to)
+                    {
+                        graph.add_edge(from, to, 1);
+                    }
+                }
+            }
+        }
+
+        let goal = Vec2::new(4, 0);
+        let path = graph
+            .astar(&Vec2::ORIGIN, &goal, |p| p.manhattan(goal))
+            .unwrap();
+        assert_eq!(path.cost, 12);
+        assert_eq!(path.nodes.len(), 13);
+        assert_eq!(
+            graph.dijkstra(&Vec2::ORIGIN, &goal).map(|p| p.cost),
+            Some(12)
+        );
+    }
+
+    #[test]
+    fn toposort() {
+        let graph = graph(&[
+            ("shirt", "tie"),
+            ("tie", "jacket"),
+            ("trousers", "shoes"),
+            ("shirt", "jacket"),
+            ("trousers", "jacket"),
+        ]);
+
+        let order = graph.toposort().unwrap();
+        let position = |node| order.iter().position(|n| **n == node).unwrap();
+        assert_eq!(order.len(), 5);
+        assert!(position("shirt") < position("tie"));
+        assert!(position("tie") < position("jacket"));
+        assert!(position("trousers") < position("jacket"));
+        assert!(position("trousers") < position("shoes"));
+    }
+
+    #[test]
+    fn toposort_reports_cycle() {
+        let graph = graph(&[("a", "b"), ("b", "c"), ("c", "d"), ("d", "b"), ("d", "e")]);
+
+        let cycle = graph.toposort().unwrap_err();
+        assert_eq!(cycle, Cycle(vec![&"b", &"c", &"d"]));
+        assert_eq!(cycle.to_string(), "cycle: b -> c -> d -> b");
+
+        let self_loop = graph_with_loop();
+        assert_eq!(self_loop.toposort().unwrap_err(), Cycle(vec![&"x"]));
+    }
+
+    fn graph_with_loop() -> Graph<&'static str> {
+        graph(&[("w", "x"), ("x", "x")])
+    }
+
+    #[test]
+    fn strongly_connected_components() {
+        let graph = graph(&[
+            ("a", "b"),
+            ("b", "c"),
+            ("c", "a"),
+            ("b", "d"),
+            ("d", "e"),
+            ("e", "d"),
+            ("e", "f"),
+            ("g", "f"),
+        ]);
+
+        let components = graph.strongly_connected_components();
+        assert_eq!(
+            components,
+            vec![
+                vec![&"g"],
+                vec![&"a", &"b", &"c"],
+                vec![&"d", &"e"],
+                vec![&"f"],
+            ]
+        );
+    }
+}
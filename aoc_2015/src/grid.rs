@@ -0,0 +1,518 @@
+use std::{
+    fmt,
+    ops::{Index, IndexMut},
+    str::FromStr,
+};
+
+use rayon::prelude::*;
+
+use crate::error::{Error, Result};
+
+/// A rectangular grid of cells, stored row by row, addressed by `(x, y)` with
+/// `(0, 0)` at the top left.
+///
+/// # Examples
+///
+/// ```rust
+/// use aoc_2015::grid::Grid;
+///
+/// let mut grid: Grid<char> = "#..\n.#.".parse().unwrap();
+/// assert_eq!((grid.width(), grid.height()), (3, 2));
+/// assert_eq!(grid.get(1, 1), Some(&'#'));
+/// assert_eq!(grid.get(3, 0), None);
+///
+/// grid[(2, 0)] = '#';
+/// assert_eq!(grid.rotate_cw().to_string(), ".#\n#.\n.#");
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    width: usize,
+    height: usize,
+}
+
+const NEIGHBOURS_4: [(isize, isize); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+
+const NEIGHBOURS_8: [(isize, isize); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+];
+
+impl<T: Clone> Grid<T> {
+    /// Creates a `width` by `height` grid filled with `value`.
+    pub fn new(width: usize, height: usize, value: T) -> Self {
+        Self {
+            cells: vec![value; width * height],
+            width,
+            height,
+        }
+    }
+
+    /// The grid turned a quarter clockwise.
+    pub fn rotate_cw(&self) -> Self {
+        Self::from_fn(self.height, self.width, |x, y| {
+            self[(y, self.height - 1 - x)].clone()
+        })
+    }
+
+    /// The grid turned a quarter counterclockwise.
+    pub fn rotate_ccw(&self) -> Self {
+        Self::from_fn(self.height, self.width, |x, y| {
+            self[(self.width - 1 - y, x)].clone()
+        })
+    }
+
+    /// The grid turned upside down.
+    pub fn rotate_180(&self) -> Self {
+        let mut cells = self.cells.clone();
+        cells.reverse();
+        Self { cells, ..*self }
+    }
+
+    /// The grid mirrored left to right.
+    pub fn flip_horizontal(&self) -> Self {
+        Self::from_fn(self.width, self.height, |x, y| {
+            self[(self.width - 1 - x, y)].clone()
+        })
+    }
+
+    /// The grid mirrored top to bottom.
+    pub fn flip_vertical(&self) -> Self {
+        Self::from_fn(self.width, self.height, |x, y| {
+            self[(x, self.height - 1 - y)].clone()
+        })
+    }
+
+    /// The grid mirrored along its main diagonal.
+    pub fn transpose(&self) -> Self {
+        Self::from_fn(self.height, self.width, |x, y| self[(y, x)].clone())
+    }
+
+    /// A copy of the `width` by `height` rectangle starting at `(x, y)`.
+    ///
+    /// # Panics
+    ///
+    /// If the rectangle does not fit in the grid.
+    pub fn sub_grid(&self, x: usize, y: usize, width: usize, height: usize) -> Self {
+        self.assert_rect(x, y, width, height);
+        Self::from_fn(width, height, |dx, dy| self[(x + dx, y + dy)].clone())
+    }
+}
+
+impl<T> Grid<T> {
+    /// Creates a `width` by `height` grid with `f(x, y)` in each cell.
+    pub fn from_fn(width: usize, height: usize, mut f: impl FnMut(usize, usize) -> T) -> Self {
+        let cells = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(|(x, y)| f(x, y))
+            .collect();
+        Self {
+            cells,
+            width,
+            height,
+        }
+    }
+
+    /// Parses a character map, one row per line, mapping each character with `f`.
+    pub fn parse_with(s: &str, mut f: impl FnMut(char) -> T) -> Result<Self> {
+        let mut cells = Vec::new();
+        let mut width = None;
+        let mut height = 0;
+
+        for (i, line) in s.lines().enumerate() {
+            let before = cells.len();
+            cells.extend(line.chars().map(&mut f));
+            let found = cells.len() - before;
+
+            match width {
+                None => width = Some(found),
+                Some(expected) if expected != found => {
+                    return Err(Error::RaggedGrid {
+                        line: i + 1,
+                        expected,
+                        found,
+                    })
+                }
+                _ => {}
+            }
+            height += 1;
+        }
+
+        Ok(Self {
+            cells,
+            width: width.unwrap_or(0),
+            height,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, x: usize, y: usize) -> bool {
+        x < self.width && y < self.height
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<&T> {
+        self.contains(x, y).then(|| &self.cells[x + y * self.width])
+    }
+
+    pub fn get_mut(&mut self, x: usize, y: usize) -> Option<&mut T> {
+        if self.contains(x, y) {
+            Some(&mut self.cells[x + y * self.width])
+        } else {
+            None
+        }
+    }
+
+    /// Returns the cell at `(x, y)` without checking the bounds.
+    ///
+    /// # Safety
+    ///
+    /// `(x, y)` must be within the grid.
+    pub unsafe fn get_unchecked(&self, x: usize, y: usize) -> &T {
+        self.cells.get_unchecked(x + y * self.width)
+    }
+
+    /// Returns the cell at `(x, y)` mutably without checking the bounds.
+    ///
+    /// # Safety
+    ///
+    /// `(x, y)` must be within the grid.
+    pub unsafe fn get_unchecked_mut(&mut self, x: usize, y: usize) -> &mut T {
+        self.cells.get_unchecked_mut(x + y * self.width)
+    }
+
+    /// Every cell, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.cells.iter()
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.cells.iter_mut()
+    }
+
+    /// Every cell along with its position, row by row.
+    pub fn cells(&self) -> impl Iterator<Item = ((usize, usize), &T)> {
+        let width = self.width;
+        self.cells
+            .iter()
+            .enumerate()
+            .map(move |(i, cell)| ((i % width, i / width), cell))
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn row_mut(&mut self, y: usize) -> &mut [T] {
+        &mut self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1)).take(self.height)
+    }
+
+    pub fn rows_mut(&mut self) -> impl Iterator<Item = &mut [T]> {
+        self.cells.chunks_mut(self.width.max(1)).take(self.height)
+    }
+
+    /// The rows, mutably, for rayon to update in parallel.
+    pub fn par_rows_mut(&mut self) -> impl IndexedParallelIterator<Item = &mut [T]>
+    where
+        T: Send,
+    {
+        self.cells.par_chunks_mut(self.width.max(1))
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        assert!(x < self.width, "column {} out of the grid", x);
+        self.cells.iter().skip(x).step_by(self.width)
+    }
+
+    /// The cells of the `width` by `height` rectangle starting at `(x, y)`,
+    /// row by row.
+    ///
+    /// # Panics
+    ///
+    /// If the rectangle does not fit in the grid.
+    pub fn rect(
+        &self,
+        x: usize,
+        y: usize,
+        width: usize,
+        height: usize,
+    ) -> impl Iterator<Item = &T> {
+        self.assert_rect(x, y, width, height);
+        self.cells
+            .chunks(self.width.max(1))
+            .skip(y)
+            .take(height)
+            .flat_map(move |row| &row[x..x + width])
+    }
+
+    /// The cells of the `width` by `height` rectangle starting at `(x, y)`,
+    /// row by row, mutably.
+    ///
+    /// # Panics
+    ///
+    /// If the rectangle does not fit in the grid.
+    pub fn rect_mut(
+        &mut self,
+        x: usize,
+        y: usize,
+        width: usize,
+        height: usize,
+    ) -> impl Iterator<Item = &mut T> {
+        self.assert_rect(x, y, width, height);
+        self.cells
+            .chunks_mut(self.width.max(1))
+            .skip(y)
+            .take(height)
+            .flat_map(move |row| &mut row[x..x + width])
+    }
+
+    fn assert_rect(&self, x: usize, y: usize, width: usize, height: usize) {
+        assert!(
+            x + width <= self.width && y + height <= self.height,
+            "rectangle {}x{} at ({}, {}) out of the {}x{} grid",
+            width,
+            height,
+            x,
+            y,
+            self.width,
+            self.height
+        );
+    }
+
+    /// The positions of the orthogonal neighbours of `(x, y)` within the grid.
+    pub fn neighbours4(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.offsets(x, y, &NEIGHBOURS_4)
+    }
+
+    /// The positions of the orthogonal and diagonal neighbours of `(x, y)`
+    /// within the grid.
+    pub fn neighbours8(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.offsets(x, y, &NEIGHBOURS_8)
+    }
+
+    fn offsets<'a>(
+        &'a self,
+        x: usize,
+        y: usize,
+        offsets: &'static [(isize, isize)],
+    ) -> impl Iterator<Item = (usize, usize)> + 'a {
+        offsets.iter().filter_map(move |&(dx, dy)| {
+            let nx = x.checked_add_signed(dx)?;
+            let ny = y.checked_add_signed(dy)?;
+            self.contains(nx, ny).then_some((nx, ny))
+        })
+    }
+
+    /// A grid of the same size with `f` applied to every cell.
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            cells: self.cells.iter().map(f).collect(),
+            width: self.width,
+            height: self.height,
+        }
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (x, y): (usize, usize)) -> &T {
+        self.get(x, y).unwrap_or_else(|| {
+            panic!(
+                "({}, {}) out of the {}x{} grid",
+                x, y, self.width, self.height
+            )
+        })
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (x, y): (usize, usize)) -> &mut T {
+        let (width, height) = (self.width, self.height);
+        self.get_mut(x, y)
+            .unwrap_or_else(|| panic!("({}, {}) out of the {}x{} grid", x, y, width, height))
+    }
+}
+
+impl FromStr for Grid<char> {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse_with(s, |c| c)
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (y, row) in self.rows().enumerate() {
+            if y > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn grid() -> Grid<char> {
+        "abc\ndef".parse().unwrap()
+    }
+
+    #[test]
+    fn parse_and_display() {
+        let grid = grid();
+
+        assert_eq!(grid.width(), 3);
+        assert_eq!(grid.height(), 2);
+        assert_eq!(grid[(2, 1)], 'f');
+        assert_eq!(grid.to_string(), "abc\ndef");
+    }
+
+    #[test]
+    fn parse_with_mapping() {
+        let grid = Grid::parse_with("#.\n.#", |c| c == '#').unwrap();
+
+        assert_eq!(grid.iter().filter(|&&on| on).count(), 2);
+        assert_eq!(grid.get(1, 1), Some(&true));
+    }
+
+    #[test]
+    fn parse_ragged() {
+        let err = "abc\nde".parse::<Grid<char>>().unwrap_err();
+
+        assert_eq!(err.to_string(), "line 2 has 2 cells, expected 3");
+    }
+
+    #[test]
+    fn parse_empty() {
+        let grid: Grid<char> = "".parse().unwrap();
+
+        assert_eq!((grid.width(), grid.height()), (0, 0));
+        assert_eq!(grid.rows().count(), 0);
+    }
+
+    #[test]
+    fn checked_access() {
+        let mut grid = grid();
+
+        assert_eq!(grid.get(3, 0), None);
+        assert_eq!(grid.get(0, 2), None);
+        assert_eq!(grid.get_mut(3, 1), None);
+
+        *grid.get_mut(0, 0).unwrap() = 'z';
+        assert_eq!(grid.row(0), &['z', 'b', 'c']);
+    }
+
+    #[test]
+    fn unchecked_access() {
+        let grid = grid();
+
+        assert_eq!(unsafe { *grid.get_unchecked(1, 1) }, 'e');
+    }
+
+    #[test]
+    #[should_panic(expected = "(3, 0) out of the 3x2 grid")]
+    fn index_out_of_range() {
+        let _ = grid()[(3, 0)];
+    }
+
+    #[test]
+    fn neighbours() {
+        let grid = Grid::new(3, 3, 0);
+
+        assert_eq!(
+            grid.neighbours4(1, 1).collect::<Vec<_>>(),
+            vec![(1, 0), (2, 1), (1, 2), (0, 1)]
+        );
+        assert_eq!(
+            grid.neighbours4(0, 0).collect::<Vec<_>>(),
+            vec![(1, 0), (0, 1)]
+        );
+        assert_eq!(grid.neighbours8(1, 1).count(), 8);
+        assert_eq!(
+            grid.neighbours8(2, 2).collect::<Vec<_>>(),
+            vec![(1, 1), (2, 1), (1, 2)]
+        );
+    }
+
+    #[test]
+    fn rows_and_columns() {
+        let grid = grid();
+
+        assert_eq!(
+            grid.rows().collect::<Vec<_>>(),
+            vec![&['a', 'b', 'c'], &['d', 'e', 'f']]
+        );
+        assert_eq!(grid.column(1).collect::<String>(), "be");
+        assert_eq!(
+            grid.cells().find(|(_, &c)| c == 'e').map(|(pos, _)| pos),
+            Some((1, 1))
+        );
+    }
+
+    #[test]
+    fn rectangles() {
+        let mut grid = Grid::from_fn(4, 3, |x, y| x + 10 * y);
+
+        assert_eq!(
+            grid.rect(1, 1, 2, 2).copied().collect::<Vec<_>>(),
+            vec![11, 12, 21, 22]
+        );
+        assert_eq!(grid.sub_grid(2, 0, 2, 2).to_string(), "23\n1213");
+
+        grid.rect_mut(0, 0, 2, 1).for_each(|cell| *cell = 0);
+        assert_eq!(grid.row(0), &[0, 0, 2, 3]);
+    }
+
+    #[test]
+    #[should_panic(expected = "rectangle 2x2 at (3, 0) out of the 4x3 grid")]
+    fn rectangle_out_of_range() {
+        let grid = Grid::new(4, 3, 0);
+        let _ = grid.rect(3, 0, 2, 2);
+    }
+
+    #[test]
+    fn rotations_and_flips() {
+        let grid = grid();
+
+        assert_eq!(grid.rotate_cw().to_string(), "da\neb\nfc");
+        assert_eq!(grid.rotate_ccw().to_string(), "cf\nbe\nad");
+        assert_eq!(grid.rotate_180().to_string(), "fed\ncba");
+        assert_eq!(grid.flip_horizontal().to_string(), "cba\nfed");
+        assert_eq!(grid.flip_vertical().to_string(), "def\nabc");
+        assert_eq!(grid.transpose().to_string(), "ad\nbe\ncf");
+        assert_eq!(grid.rotate_cw().rotate_ccw(), grid);
+        assert_eq!(grid.rotate_cw().rotate_cw(), grid.rotate_180());
+    }
+
+    #[test]
+    fn map_cells() {
+        let grid = grid().map(|c| c.to_ascii_uppercase());
+
+        assert_eq!(grid.to_string(), "ABC\nDEF");
+    }
+}
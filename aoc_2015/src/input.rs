@@ -1,6 +1,293 @@
+use std::{
+    fmt::Display,
+    fs,
+    panic::{self, RefUnwindSafe},
+    path::{Path, PathBuf},
+};
+
+use crate::error::{Error, Result};
+
+/// Embeds a puzzle input at compile time.
+///
+/// `input!("day6")` reads the default `input/2015/day6.txt`, while
+/// `input!("day6", "alice")` reads the named `input/2015/day6/alice.txt`.
 #[macro_export]
 macro_rules! input {
     ($day:literal) => {{
         include_str!(concat!("../input/2015/", $day, ".txt"))
     }};
+    ($day:literal, $name:literal) => {{
+        include_str!(concat!("../input/2015/", $day, "/", $name, ".txt"))
+    }};
+}
+
+/// The name given to the legacy `input/2015/dayN.txt` file.
+pub const DEFAULT_INPUT: &str = "default";
+
+/// The directory holding every input of the year.
+pub fn input_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("input/2015")
+}
+
+/// The expected answers of a single input, read from the `.answers` file
+/// next to it. Each line is `part1 = <answer>` or `part2 = <answer>`.
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct Answers {
+    pub part1: Option<String>,
+    pub part2: Option<String>,
+}
+
+impl Answers {
+    fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        Self::parse(&read(path)?, path)
+    }
+
+    fn parse(content: &str, path: &Path) -> Result<Self> {
+        let mut answers = Self::default();
+        for (i, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let invalid = || Error::InvalidAnswer {
+                path: path.to_path_buf(),
+                line: i + 1,
+                entry: line.to_string(),
+            };
+            let (part, answer) = line.split_once('=').ok_or_else(invalid)?;
+            let answer = Some(answer.trim().to_string());
+            match part.trim() {
+                "part1" => answers.part1 = answer,
+                "part2" => answers.part2 = answer,
+                _ => return Err(invalid()),
+            }
+        }
+
+        Ok(answers)
+    }
+
+    /// Returns the expected answer of the given part, if known.
+    pub fn get(&self, part: u8) -> Option<&str> {
+        match part {
+            1 => self.part1.as_deref(),
+            2 => self.part2.as_deref(),
+            _ => None,
+        }
+    }
+}
+
+/// A puzzle input loaded at runtime, along with its expected answers.
+#[derive(Debug, Clone)]
+pub struct NamedInput {
+    pub name: String,
+    pub path: PathBuf,
+    pub text: String,
+    pub answers: Answers,
+}
+
+impl NamedInput {
+    fn load(name: String, path: PathBuf) -> Result<Self> {
+        // Same as `aoc_runner::ArcStr`, so every runner sees the same text.
+        let text = read(&path)?.trim_end_matches('\n').to_string();
+        let answers = Answers::load(&path.with_extension("answers"))?;
+        Ok(Self {
+            name,
+            path,
+            text,
+            answers,
+        })
+    }
+}
+
+/// Loads every input of a day: the default `dayN.txt`, if present, followed by
+/// the named `dayN/*.txt` inputs sorted by name.
+pub fn inputs(day: &str) -> Result<Vec<NamedInput>> {
+    let dir = input_dir();
+    let mut inputs = Vec::new();
+
+    let default = dir.join(format!("{day}.txt"));
+    if default.exists() {
+        inputs.push(NamedInput::load(DEFAULT_INPUT.to_string(), default)?);
+    }
+
+    let named_dir = dir.join(day);
+    if named_dir.is_dir() {
+        let entries = fs::read_dir(&named_dir).map_err(|source| Error::Io {
+            path: named_dir.clone(),
+            source,
+        })?;
+
+        let mut named = Vec::new();
+        for entry in entries {
+            let path = entry
+                .map_err(|source| Error::Io {
+                    path: named_dir.clone(),
+                    source,
+                })?
+                .path();
+            if path.extension().is_some_and(|ext| ext == "txt") {
+                let name = path.file_stem().unwrap().to_string_lossy().into_owned();
+                named.push((name, path));
+            }
+        }
+
+        named.sort();
+        for (name, path) in named {
+            inputs.push(NamedInput::load(name, path)?);
+        }
+    }
+
+    Ok(inputs)
+}
+
+/// Runs `solve` against every input of `day` that has an expected answer for
+/// `part`, and panics with a per-input report if any of them disagrees.
+///
+/// Meant for the integration tests, so a solver passing one account's input
+/// but failing another's gets caught. Also panics when no input has an answer
+/// for `part`, so that a checkout without answers does not pass silently.
+pub fn check_inputs<T, F>(day: &str, part: u8, solve: F)
+where
+    T: Display,
+    F: Fn(&str) -> T + RefUnwindSafe,
+{
+    let inputs = inputs(day).unwrap_or_else(|e| panic!("{e}"));
+    check(&inputs, day, part, solve);
+}
+
+fn check<T, F>(inputs: &[NamedInput], day: &str, part: u8, solve: F)
+where
+    T: Display,
+    F: Fn(&str) -> T + RefUnwindSafe,
+{
+    if inputs.iter().all(|input| input.answers.get(part).is_none()) {
+        panic!("{} part {}: no input has an expected answer", day, part);
+    }
+
+    let failures: Vec<_> = inputs
+        .iter()
+        .filter_map(|input| {
+            let expected = input.answers.get(part)?;
+            let actual = match panic::catch_unwind(|| solve(&input.text).to_string()) {
+                Ok(actual) => actual,
+                Err(_) => return Some(format!("  {}: panicked", input.name)),
+            };
+            (actual != expected)
+                .then(|| format!("  {}: expected {}, got {}", input.name, expected, actual))
+        })
+        .collect();
+
+    if !failures.is_empty() {
+        panic!(
+            "{} part {} failed for {} input(s):\n{}",
+            day,
+            part,
+            failures.len(),
+            failures.join("\n")
+        );
+    }
+}
+
+pub(crate) fn read(path: &Path) -> Result<String> {
+    fs::read_to_string(path).map_err(|source| Error::Io {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn parse_answers() {
+        let content = "# alice\npart1 = 543903\n\npart2=14687245\n";
+        let answers = Answers::parse(content, Path::new("alice.answers")).unwrap();
+
+        assert_eq!(answers.get(1), Some("543903"));
+        assert_eq!(answers.get(2), Some("14687245"));
+        assert_eq!(answers.get(3), None);
+    }
+
+    #[test]
+    fn parse_partial_answers() {
+        let answers = Answers::parse("part2 = abc", Path::new("bob.answers")).unwrap();
+
+        assert_eq!(answers.get(1), None);
+        assert_eq!(answers.get(2), Some("abc"));
+    }
+
+    #[test]
+    fn parse_invalid_answers() {
+        let err = Answers::parse("part1 = 1\npart3 = 2", Path::new("bob.answers")).unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "bob.answers:2: invalid answer entry `part3 = 2`"
+        );
+    }
+
+    fn input(name: &str, text: &str, part1: Option<&str>) -> NamedInput {
+        NamedInput {
+            name: name.to_string(),
+            path: PathBuf::from(format!("{}.txt", name)),
+            text: text.to_string(),
+            answers: Answers {
+                part1: part1.map(str::to_string),
+                part2: None,
+            },
+        }
+    }
+
+    /// The message `check` panics with, if it does.
+    fn check_report(inputs: &[NamedInput], part: u8) -> Option<String> {
+        let solve = |text: &str| {
+            assert_ne!(text, "boom", "boom");
+            text.len()
+        };
+        let payload = panic::catch_unwind(|| check(inputs, "day0", part, solve)).err()?;
+        payload.downcast::<String>().ok().map(|message| *message)
+    }
+
+    #[test]
+    fn check_reports_every_failure() {
+        let inputs = [
+            input("alice", "ab", Some("2")),
+            input("bob", "abc", Some("4")),
+            input("carol", "boom", Some("4")),
+            input("dave", "x", None),
+        ];
+
+        assert_eq!(
+            check_report(&inputs, 1).unwrap(),
+            "day0 part 1 failed for 2 input(s):\n  bob: expected 4, got 3\n  carol: panicked"
+        );
+        assert_eq!(check_report(&inputs[..1], 1), None);
+    }
+
+    #[test]
+    fn check_without_answers() {
+        let inputs = [input("alice", "ab", Some("2"))];
+
+        assert_eq!(
+            check_report(&inputs, 2).unwrap(),
+            "day0 part 2: no input has an expected answer"
+        );
+        assert_eq!(
+            check_report(&[], 1).unwrap(),
+            "day0 part 1: no input has an expected answer"
+        );
+    }
+
+    #[test]
+    fn missing_day_has_no_inputs() {
+        assert!(inputs("day0").unwrap().is_empty());
+    }
 }
@@ -0,0 +1,119 @@
+use std::{collections::HashMap, fmt};
+
+/// The dense ID of a name in an [`Interner`], usable as an index.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
+pub struct Symbol(u32);
+
+impl Symbol {
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+impl fmt::Display for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{}", self.0)
+    }
+}
+
+/// Maps names to [`Symbol`]s numbered from 0 in the order they are first
+/// seen, and back.
+///
+/// # Examples
+///
+/// ```rust
+/// use aoc_2015::interner::Interner;
+///
+/// let mut interner = Interner::new();
+/// let london = interner.intern("London");
+/// let dublin = interner.intern("Dublin");
+///
+/// assert_eq!(interner.intern("London"), london);
+/// assert_eq!((london.index(), dublin.index()), (0, 1));
+/// assert_eq!(interner.resolve(dublin), "Dublin");
+/// assert_eq!(interner.get("Belfast"), None);
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct Interner {
+    symbols: HashMap<String, Symbol>,
+    names: Vec<String>,
+}
+
+impl Interner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the symbol of `name`, allocating the next one if it is new.
+    pub fn intern(&mut self, name: &str) -> Symbol {
+        if let Some(&symbol) = self.symbols.get(name) {
+            return symbol;
+        }
+        let symbol = Symbol(u32::try_from(self.names.len()).expect("Too many names to intern"));
+        self.symbols.insert(name.to_string(), symbol);
+        self.names.push(name.to_string());
+        symbol
+    }
+
+    pub fn get(&self, name: &str) -> Option<Symbol> {
+        self.symbols.get(name).copied()
+    }
+
+    /// The name of `symbol`.
+    ///
+    /// # Panics
+    ///
+    /// If `symbol` comes from another interner with more names.
+    pub fn resolve(&self, symbol: Symbol) -> &str {
+        &self.names[symbol.index()]
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// Every symbol, in order.
+    pub fn symbols(&self) -> impl Iterator<Item = Symbol> {
+        (0..self.names.len() as u32).map(Symbol)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn dense_ids() {
+        let mut interner = Interner::new();
+        assert!(interner.is_empty());
+
+        let symbols = ["b", "a", "b", "c", "a"].map(|name| interner.intern(name));
+
+        assert_eq!(symbols.map(Symbol::index), [0, 1, 0, 2, 1]);
+        assert_eq!(interner.len(), 3);
+        assert_eq!(
+            interner
+                .symbols()
+                .map(|symbol| interner.resolve(symbol))
+                .collect::<Vec<_>>(),
+            ["b", "a", "c"]
+        );
+    }
+
+    #[test]
+    fn lookup_without_interning() {
+        let mut interner = Interner::new();
+        let a = interner.intern("a");
+
+        assert_eq!(interner.get("a"), Some(a));
+        assert_eq!(interner.get("z"), None);
+        assert_eq!(interner.len(), 1);
+        assert_eq!(a.to_string(), "#0");
+    }
+}
@@ -0,0 +1,286 @@
+use std::ops::{Add, ControlFlow};
+
+use crate::rle::Runs;
+
+/// Folds and scans that stop early, for puzzles asking *when* a running value
+/// first does something.
+pub trait IteratorExt: Iterator + Sized {
+    /// Folds every item into an accumulator, until `fold` breaks.
+    ///
+    /// Returns the break value, or the final accumulator if `fold` never
+    /// broke. This is [`Iterator::try_fold`] without needing a `&mut`
+    /// iterator.
+    ///
+    /// ```rust
+    /// use std::ops::ControlFlow;
+    ///
+    /// use aoc_2015::iter::IteratorExt;
+    ///
+    /// let over_five = [1, 2, 3, 4].into_iter().fold_until(0, |acc, x| {
+    ///     let acc = acc + x;
+    ///     if acc > 5 {
+    ///         ControlFlow::Break(acc)
+    ///     } else {
+    ///         ControlFlow::Continue(acc)
+    ///     }
+    /// });
+    /// assert_eq!(over_five, ControlFlow::Break(6));
+    ///
+    /// let never = [1, 2]
+    ///     .into_iter()
+    ///     .fold_until(0, |acc, x| ControlFlow::<(), _>::Continue(acc + x));
+    /// assert_eq!(never, ControlFlow::Continue(3));
+    /// ```
+    fn fold_until<T, B, F>(self, init: T, mut fold: F) -> ControlFlow<B, T>
+    where
+        F: FnMut(T, Self::Item) -> ControlFlow<B, T>,
+    {
+        let mut acc = init;
+        for item in self {
+            acc = fold(acc, item)?;
+        }
+        ControlFlow::Continue(acc)
+    }
+
+    /// Like [`Iterator::scan`], but `scan` tells with [`ControlFlow::Break`]
+    /// which value is the last one, instead of returning `None` after it.
+    ///
+    /// ```rust
+    /// use std::ops::ControlFlow;
+    ///
+    /// use aoc_2015::iter::IteratorExt;
+    ///
+    /// let totals: Vec<_> = [3, 4, 5, 6]
+    ///     .into_iter()
+    ///     .scan_until(0, |total, x| {
+    ///         *total += x;
+    ///         if *total >= 10 {
+    ///             ControlFlow::Break(*total)
+    ///         } else {
+    ///             ControlFlow::Continue(*total)
+    ///         }
+    ///     })
+    ///     .collect();
+    /// assert_eq!(totals, [3, 7, 12]);
+    /// ```
+    fn scan_until<St, B, F>(self, init: St, scan: F) -> ScanUntil<Self, St, F>
+    where
+        F: FnMut(&mut St, Self::Item) -> ControlFlow<B, B>,
+    {
+        ScanUntil {
+            iter: self,
+            state: init,
+            scan,
+            done: false,
+        }
+    }
+
+    /// The first running total of the items that satisfies `predicate`.
+    ///
+    /// ```rust
+    /// use aoc_2015::iter::IteratorExt;
+    ///
+    /// let floors = [1, -1, -1, 1];
+    /// assert_eq!(floors.into_iter().first_prefix_sum_where(|&f| f < 0), Some(-1));
+    /// assert_eq!(floors.into_iter().first_prefix_sum_where(|&f| f > 1), None);
+    /// ```
+    fn first_prefix_sum_where<P>(self, mut predicate: P) -> Option<Self::Item>
+    where
+        Self::Item: Copy + Default + Add<Output = Self::Item>,
+        P: FnMut(&Self::Item) -> bool,
+    {
+        self.scan(Self::Item::default(), |total, item| {
+            *total = *total + item;
+            Some(*total)
+        })
+        .find(|total| predicate(total))
+    }
+
+    /// The index of the item whose running total first satisfies
+    /// `predicate`.
+    ///
+    /// ```rust
+    /// use aoc_2015::iter::IteratorExt;
+    ///
+    /// let floors = [1, -1, -1, 1];
+    /// assert_eq!(floors.into_iter().position_of_cumulative(|&f| f < 0), Some(2));
+    /// assert_eq!(floors.into_iter().position_of_cumulative(|&f| f > 1), None);
+    /// ```
+    fn position_of_cumulative<P>(mut self, mut predicate: P) -> Option<usize>
+    where
+        Self::Item: Copy + Default + Add<Output = Self::Item>,
+        P: FnMut(&Self::Item) -> bool,
+    {
+        let mut total = Self::Item::default();
+        self.position(|item| {
+            total = total + item;
+            predicate(&total)
+        })
+    }
+
+    /// Yields items up to and including the first one satisfying `predicate`,
+    /// where [`Iterator::take_while`] would stop before it.
+    ///
+    /// ```rust
+    /// use aoc_2015::iter::IteratorExt;
+    ///
+    /// let taken: Vec<_> = [1, 3, 4, 5, 6]
+    ///     .into_iter()
+    ///     .take_until_inclusive(|x| x % 2 == 0)
+    ///     .collect();
+    /// assert_eq!(taken, [1, 3, 4]);
+    ///
+    /// let all: Vec<_> = [1, 3]
+    ///     .into_iter()
+    ///     .take_until_inclusive(|x| x % 2 == 0)
+    ///     .collect();
+    /// assert_eq!(all, [1, 3]);
+    /// ```
+    fn take_until_inclusive<P>(self, predicate: P) -> TakeUntilInclusive<Self, P>
+    where
+        P: FnMut(&Self::Item) -> bool,
+    {
+        TakeUntilInclusive {
+            iter: self,
+            predicate,
+            done: false,
+        }
+    }
+
+    /// Groups runs of equal items into `(value, count)` pairs.
+    ///
+    /// ```rust
+    /// use aoc_2015::iter::IteratorExt;
+    ///
+    /// let runs: Vec<_> = "aaabccca".chars().runs().collect();
+    /// assert_eq!(runs, [('a', 3), ('b', 1), ('c', 3), ('a', 1)]);
+    /// ```
+    fn runs(self) -> Runs<Self>
+    where
+        Self::Item: PartialEq,
+    {
+        Runs::new(self)
+    }
+}
+
+impl<I: Iterator> IteratorExt for I {}
+
+/// The iterator of [`IteratorExt::scan_until`].
+pub struct ScanUntil<I, St, F> {
+    iter: I,
+    state: St,
+    scan: F,
+    done: bool,
+}
+
+impl<I, St, B, F> Iterator for ScanUntil<I, St, F>
+where
+    I: Iterator,
+    F: FnMut(&mut St, I::Item) -> ControlFlow<B, B>,
+{
+    type Item = B;
+
+    fn next(&mut self) -> Option<B> {
+        if self.done {
+            return None;
+        }
+        match (self.scan)(&mut self.state, self.iter.next()?) {
+            ControlFlow::Continue(value) => Some(value),
+            ControlFlow::Break(value) => {
+                self.done = true;
+                Some(value)
+            }
+        }
+    }
+}
+
+/// The iterator of [`IteratorExt::take_until_inclusive`].
+pub struct TakeUntilInclusive<I, P> {
+    iter: I,
+    predicate: P,
+    done: bool,
+}
+
+impl<I, P> Iterator for TakeUntilInclusive<I, P>
+where
+    I: Iterator,
+    P: FnMut(&I::Item) -> bool,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<I::Item> {
+        if self.done {
+            return None;
+        }
+        let item = self.iter.next()?;
+        self.done = (self.predicate)(&item);
+        Some(item)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn fold_until_breaks_early() {
+        let mut seen = Vec::new();
+        let result = (1..).fold_until(0, |acc, x| {
+            seen.push(x);
+            if acc + x > 5 {
+                ControlFlow::Break(acc + x)
+            } else {
+                ControlFlow::Continue(acc + x)
+            }
+        });
+
+        assert_eq!(result, ControlFlow::Break(6));
+        assert_eq!(seen, [1, 2, 3]);
+    }
+
+    #[test]
+    fn fold_until_empty() {
+        let result = std::iter::empty::<i32>().fold_until(7, |_, _| ControlFlow::Break(()));
+        assert_eq!(result, ControlFlow::Continue(7));
+    }
+
+    #[test]
+    fn scan_until_stops_consuming() {
+        let mut iter = [1, 2, 3, 4].into_iter();
+        let scanned: Vec<_> = iter
+            .by_ref()
+            .scan_until((), |_, x| {
+                if x == 2 {
+                    ControlFlow::Break(x * 10)
+                } else {
+                    ControlFlow::Continue(x * 10)
+                }
+            })
+            .collect();
+
+        assert_eq!(scanned, [10, 20]);
+        assert_eq!(iter.next(), Some(3));
+    }
+
+    #[test]
+    fn cumulative_on_empty() {
+        assert_eq!(
+            std::iter::empty::<i32>().first_prefix_sum_where(|_| true),
+            None
+        );
+        assert_eq!(
+            std::iter::empty::<i32>().position_of_cumulative(|_| true),
+            None
+        );
+    }
+
+    #[test]
+    fn take_until_inclusive_is_fused() {
+        let mut taken = [1, 2, 3].into_iter().take_until_inclusive(|&x| x == 1);
+        assert_eq!(taken.next(), Some(1));
+        assert_eq!(taken.next(), None);
+        assert_eq!(taken.next(), None);
+    }
+}
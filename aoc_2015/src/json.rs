@@ -0,0 +1,487 @@
+//! A JSON parser building a tree that borrows from the document, and a fold
+//! over the tree that can skip whole subtrees.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use aoc_2015::json::{parse, Value, Walk};
+//!
+//! let document = parse(r#"[1, {"c": "red", "b": 2}, 3]"#).unwrap();
+//! let sum = |prune_red: bool| {
+//!     document.fold(0, |sum, value| match value {
+//!         Value::Object(members)
+//!             if prune_red && members.iter().any(|(_, v)| v.as_str() == Some("red")) =>
+//!         {
+//!             (sum, Walk::Prune)
+//!         }
+//!         value => (sum + value.as_i64().unwrap_or(0), Walk::Descend),
+//!     })
+//! };
+//! assert_eq!((sum(false), sum(true)), (6, 4));
+//!
+//! let error = parse("[1, 2,]").unwrap_err();
+//! assert_eq!(error.to_string(), "expected value at byte 6");
+//! ```
+
+use std::borrow::Cow;
+
+use derive_more::derive::{Display, Error};
+use nom::{
+    branch::alt,
+    bytes::complete::tag,
+    character::complete::{anychar, char, digit0, digit1, multispace0, none_of, one_of},
+    combinator::{all_consuming, cut, map, opt, recognize},
+    error::{context, VerboseError, VerboseErrorKind},
+    multi::{many0_count, separated_list1},
+    sequence::{delimited, pair, preceded, terminated, tuple},
+};
+
+use crate::{
+    codec::{self, Dialect, EscapeError},
+    parse::{describe, ParseResult},
+};
+
+/// How deep arrays and objects may nest, so that the parser, which recurses
+/// into them, cannot run out of stack.
+pub const MAX_DEPTH: usize = 128;
+
+/// A JSON value. Strings borrow from the document unless they have escapes.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value<'a> {
+    Null,
+    Bool(bool),
+    /// A number as written, to be read as whichever type fits it.
+    Number(&'a str),
+    String(Cow<'a, str>),
+    Array(Vec<Value<'a>>),
+    /// The members in document order, keeping any duplicate keys.
+    Object(Vec<(Cow<'a, str>, Value<'a>)>),
+}
+
+/// Whether [`Value::fold`] goes on into the values inside a value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Walk {
+    Descend,
+    Prune,
+}
+
+impl<'a> Value<'a> {
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// The number, if it is an integer that fits an `i64`.
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            Value::Number(n) => n.parse().ok(),
+            _ => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Value::Number(n) => n.parse().ok(),
+            _ => None,
+        }
+    }
+
+    /// The value of the first member named `key`, for an object.
+    pub fn get(&self, key: &str) -> Option<&Value<'a>> {
+        match self {
+            Value::Object(members) => members
+                .iter()
+                .find_map(|(name, value)| (name == key).then_some(value)),
+            _ => None,
+        }
+    }
+
+    /// Folds this value and the values inside it, depth first in document
+    /// order. Returning [`Walk::Prune`] for an array or object skips what is
+    /// inside it.
+    pub fn fold<T>(&self, init: T, mut f: impl FnMut(T, &Value<'a>) -> (T, Walk)) -> T {
+        let mut acc = init;
+        let mut stack = vec![self];
+        while let Some(value) = stack.pop() {
+            let walk;
+            (acc, walk) = f(acc, value);
+            if walk == Walk::Prune {
+                continue;
+            }
+            match value {
+                Value::Array(items) => stack.extend(items.iter().rev()),
+                Value::Object(members) => stack.extend(members.iter().rev().map(|(_, v)| v)),
+                _ => {}
+            }
+        }
+        acc
+    }
+
+    /// Calls `visit` on the values [`Value::fold`] would fold.
+    pub fn visit(&self, mut visit: impl FnMut(&Value<'a>) -> Walk) {
+        self.fold((), |(), value| ((), visit(value)));
+    }
+}
+
+/// Why a document is not JSON, and where.
+#[derive(Debug, Clone, PartialEq, Eq, Display, Error)]
+#[display("{message} at byte {offset}")]
+pub struct JsonError {
+    pub offset: usize,
+    pub message: String,
+}
+
+impl JsonError {
+    fn new(input: &str, error: nom::Err<VerboseError<&str>>) -> Self {
+        let (remaining, message) = match error {
+            nom::Err::Incomplete(_) => ("", "unexpected end of input".to_string()),
+            nom::Err::Error(error) | nom::Err::Failure(error) => describe(&error),
+        };
+        JsonError {
+            offset: input.len() - remaining.len(),
+            message,
+        }
+    }
+}
+
+/// Parses a whole document, with any value at the top.
+pub fn parse(input: &str) -> Result<Value<'_>, JsonError> {
+    all_consuming(delimited(multispace0, |i| parse_value(i, 0), multispace0))(input)
+        .map(|(_, value)| value)
+        .map_err(|error| JsonError::new(input, error))
+}
+
+fn parse_value(input: &str, depth: usize) -> ParseResult<'_, Value<'_>> {
+    context(
+        "value",
+        alt((
+            map(tag("null"), |_| Value::Null),
+            map(tag("true"), |_| Value::Bool(true)),
+            map(tag("false"), |_| Value::Bool(false)),
+            map(parse_number, Value::Number),
+            map(parse_string, Value::String),
+            |i| parse_array(i, depth),
+            |i| parse_object(i, depth),
+        )),
+    )(input)
+}
+
+/// A value and the whitespace after it, inside an array or object.
+fn parse_element(input: &str, depth: usize) -> ParseResult<'_, Value<'_>> {
+    terminated(|i| parse_value(i, depth), multispace0)(input)
+}
+
+fn parse_number(input: &str) -> ParseResult<'_, &str> {
+    let digits = || cut(context("digits", digit1));
+    recognize(tuple((
+        opt(char('-')),
+        alt((tag("0"), recognize(pair(one_of("123456789"), digit0)))),
+        opt(pair(char('.'), digits())),
+        opt(tuple((one_of("eE"), opt(one_of("+-")), digits()))),
+    )))(input)
+}
+
+fn parse_string(input: &str) -> ParseResult<'_, Cow<'_, str>> {
+    let (rest, literal) = recognize(preceded(
+        char('"'),
+        cut(terminated(
+            many0_count(alt((preceded(char('\\'), anychar), none_of("\"\\")))),
+            context("closing quote", char('"')),
+        )),
+    ))(input)?;
+
+    let body = &literal[1..literal.len() - 1];
+    if !body.bytes().any(|b| b == b'\\' || b < 0x20) {
+        return Ok((rest, Cow::Borrowed(body)));
+    }
+    match codec::decode(literal, Dialect::Json) {
+        Ok(bytes) => {
+            let decoded = String::from_utf8(bytes).expect("JSON escapes decode to UTF-8");
+            Ok((rest, Cow::Owned(decoded)))
+        }
+        Err(error) => {
+            let (offset, expected) = match error {
+                EscapeError::UnescapedByte { offset, .. } => (offset, "escaped control character"),
+                EscapeError::InvalidHex { offset } => (offset, "four hex digits"),
+                EscapeError::InvalidCodePoint { offset } => (offset, "valid code point"),
+                EscapeError::UnfinishedEscape { offset }
+                | EscapeError::UnknownEscape { offset, .. }
+                | EscapeError::UnescapedQuote { offset }
+                | EscapeError::OutOfRange { offset } => (offset, "valid escape"),
+                EscapeError::MissingQuotes { .. } => (0, "string"),
+            };
+            Err(failure(&input[offset..], expected))
+        }
+    }
+}
+
+fn parse_array(input: &str, depth: usize) -> ParseResult<'_, Value<'_>> {
+    let (rest, _) = char('[')(input)?;
+    check_depth(input, depth)?;
+    let (input, _) = multispace0(rest)?;
+
+    let (input, items) = cut(alt((
+        map(char(']'), |_| Vec::new()),
+        terminated(
+            separated_list1(
+                pair(char(','), multispace0),
+                cut(|i| parse_element(i, depth + 1)),
+            ),
+            context("`,` or `]`", char(']')),
+        ),
+    )))(input)?;
+    Ok((input, Value::Array(items)))
+}
+
+fn parse_object(input: &str, depth: usize) -> ParseResult<'_, Value<'_>> {
+    let (rest, _) = char('{')(input)?;
+    check_depth(input, depth)?;
+    let (input, _) = multispace0(rest)?;
+
+    let member = |input| {
+        let (input, key) = context("string", parse_string)(input)?;
+        let (input, _) = tuple((multispace0, context("`:`", char(':')), multispace0))(input)?;
+        let (input, value) = parse_element(input, depth + 1)?;
+        Ok((input, (key, value)))
+    };
+    let (input, members) = cut(alt((
+        map(char('}'), |_| Vec::new()),
+        terminated(
+            separated_list1(pair(char(','), multispace0), cut(member)),
+            context("`,` or `}`", char('}')),
+        ),
+    )))(input)?;
+    Ok((input, Value::Object(members)))
+}
+
+fn check_depth(input: &str, depth: usize) -> ParseResult<'_, ()> {
+    if depth < MAX_DEPTH {
+        Ok((input, ()))
+    } else {
+        Err(failure(input, "fewer nested arrays and objects"))
+    }
+}
+
+/// A failure at `input`, where `expected` should have been.
+fn failure<'a>(input: &'a str, expected: &'static str) -> nom::Err<VerboseError<&'a str>> {
+    nom::Err::Failure(VerboseError {
+        errors: vec![(input, VerboseErrorKind::Context(expected))],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::*;
+
+    /// The sum of the numbers of day 12, skipping objects with a `"red"`
+    /// value in part two.
+    fn sum(document: &str, skip_red: bool) -> i64 {
+        parse(document).unwrap().fold(0, |sum, value| match value {
+            Value::Object(members)
+                if skip_red && members.iter().any(|(_, v)| v.as_str() == Some("red")) =>
+            {
+                (sum, Walk::Prune)
+            }
+            value => (sum + value.as_i64().unwrap_or(0), Walk::Descend),
+        })
+    }
+
+    #[rstest]
+    #[case("[1,2,3]", 6)]
+    #[case(r#"{"a":2,"b":4}"#, 6)]
+    #[case("[[[3]]]", 3)]
+    #[case(r#"{"a":{"b":4},"c":-1}"#, 3)]
+    #[case(r#"{"a":[-1,1]}"#, 0)]
+    #[case(r#"[-1,{"a":1}]"#, 0)]
+    #[case("[]", 0)]
+    #[case("{}", 0)]
+    fn example_part_one(#[case] document: &str, #[case] expected: i64) {
+        assert_eq!(sum(document, false), expected);
+    }
+
+    #[rstest]
+    #[case("[1,2,3]", 6)]
+    #[case(r#"[1,{"c":"red","b":2},3]"#, 4)]
+    #[case(r#"{"d":"red","e":[1,2,3,4],"f":5}"#, 0)]
+    #[case(r#"[1,"red",5]"#, 6)]
+    fn example_part_two(#[case] document: &str, #[case] expected: i64) {
+        assert_eq!(sum(document, true), expected);
+    }
+
+    #[test]
+    fn tree_of_every_kind() {
+        let document = parse(r#" {"a": [null, true, false, -1.5e3], "b\n": "c", "a": {}} "#);
+        assert_eq!(
+            document.unwrap(),
+            Value::Object(vec![
+                (
+                    "a".into(),
+                    Value::Array(vec![
+                        Value::Null,
+                        Value::Bool(true),
+                        Value::Bool(false),
+                        Value::Number("-1.5e3"),
+                    ])
+                ),
+                ("b\n".into(), Value::String("c".into())),
+                ("a".into(), Value::Object(vec![])),
+            ])
+        );
+    }
+
+    #[test]
+    fn strings_borrow_unless_escaped() {
+        let Value::Array(items) = parse(r#"["plain", "esc\"aped"]"#).unwrap() else {
+            panic!("not an array");
+        };
+        assert!(matches!(&items[0], Value::String(Cow::Borrowed("plain"))));
+        assert!(matches!(&items[1], Value::String(Cow::Owned(s)) if s == "esc\"aped"));
+    }
+
+    #[test]
+    fn numbers_as_written() {
+        let number = parse("12345678901234567890").unwrap();
+        assert_eq!(number.as_i64(), None);
+        assert_eq!(number.as_f64(), Some(12345678901234567890.0));
+        assert_eq!(parse("-0").unwrap().as_i64(), Some(0));
+        assert_eq!(parse("2.5").unwrap().as_i64(), None);
+    }
+
+    #[test]
+    fn get_first_member() {
+        let document = parse(r#"{"a": 1, "b": 2, "a": 3}"#).unwrap();
+        assert_eq!(document.get("a").and_then(Value::as_i64), Some(1));
+        assert_eq!(document.get("c"), None);
+        assert_eq!(Value::Null.get("a"), None);
+    }
+
+    #[test]
+    fn visit_in_document_order() {
+        let document = parse(r#"[1, [2, [3]], {"x": [4], "y": 5}, 6]"#).unwrap();
+        let mut seen = Vec::new();
+        document.visit(|value| {
+            seen.extend(value.as_i64());
+            match value {
+                Value::Object(_) => Walk::Prune,
+                _ => Walk::Descend,
+            }
+        });
+        assert_eq!(seen, [1, 2, 3, 6]);
+    }
+
+    #[test]
+    fn nesting_limit() {
+        let nested = |depth| format!("{}{}", "[".repeat(depth), "]".repeat(depth));
+        assert!(parse(&nested(MAX_DEPTH)).is_ok());
+
+        let error = parse(&nested(MAX_DEPTH + 1)).unwrap_err();
+        assert_eq!(error.offset, MAX_DEPTH);
+
+        let error = parse(&"[{\"a\":".repeat(100_000)).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "expected fewer nested arrays and objects at byte 384"
+        );
+    }
+
+    // Samples in the spirit of JSONTestSuite: documents any parser must
+    // accept...
+    #[rstest]
+    #[case("[]")]
+    #[case("{}")]
+    #[case(" [1] ")]
+    #[case("\t\r\n[\n1\t,\r2 ]\n")]
+    #[case("null")]
+    #[case("42")]
+    #[case(r#""top-level string""#)]
+    #[case("[-0]")]
+    #[case("[0.5]")]
+    #[case("[1E22]")]
+    #[case("[1e-2]")]
+    #[case("[123.456e+78]")]
+    #[case("[-123]")]
+    #[case("[[[[]]]]")]
+    #[case(r#"{"":0}"#)]
+    #[case(r#"{"a":"b","a":"c"}"#)]
+    #[case(r#"{"a" : [ ] , "b" : { } }"#)]
+    #[case(r#"["`Īካ"]"#)]
+    #[case(r#"["😹"]"#)]
+    #[case(r#"["\"\\\/\b\f\n\r\t"]"#)]
+    #[case(r#"["\u0000"]"#)]
+    #[case("[\"€𝄞\"]")]
+    #[case("[\"\u{7f}\"]")]
+    fn accept(#[case] document: &str) {
+        if let Err(error) = parse(document) {
+            panic!("{:?}: {}", document, error);
+        }
+    }
+
+    // ...and documents it must reject, where they go wrong.
+    #[rstest]
+    #[case("", 0)]
+    #[case(" ", 1)]
+    #[case("[", 1)]
+    #[case("]", 0)]
+    #[case("[1,]", 3)]
+    #[case("[,1]", 1)]
+    #[case("[1,,2]", 3)]
+    #[case("[1 2]", 3)]
+    #[case("[1]]", 3)]
+    #[case("[1] x", 4)]
+    #[case("[[[", 3)]
+    #[case(r#"{"a"}"#, 4)]
+    #[case(r#"{"a":1,}"#, 7)]
+    #[case(r#"{"a":1}}"#, 7)]
+    #[case(r#"{"a" : 1 "b": 2}"#, 9)]
+    #[case(r#"{"a":"#, 5)]
+    #[case("{a:1}", 1)]
+    #[case("{'a':1}", 1)]
+    #[case("{1:1}", 1)]
+    #[case("[01]", 2)]
+    #[case("[1.]", 3)]
+    #[case("[.5]", 1)]
+    #[case("[+1]", 1)]
+    #[case("[-]", 1)]
+    #[case("[1e]", 3)]
+    #[case("[1e+]", 4)]
+    #[case("[0x1]", 2)]
+    #[case("[NaN]", 1)]
+    #[case("[Infinity]", 1)]
+    #[case("[True]", 1)]
+    #[case("[nul]", 1)]
+    #[case(r#"["a]"#, 4)]
+    #[case(r#"["\"#, 2)]
+    #[case(r#"["\x41"]"#, 2)]
+    #[case(r#"["\a"]"#, 2)]
+    #[case(r#"["\u12g4"]"#, 2)]
+    #[case(r#"["\ud800"]"#, 2)]
+    #[case(r#"["\ud800A"]"#, 2)]
+    #[case("[\"a\tb\"]", 3)]
+    #[case("[\"a\nb\"]", 3)]
+    #[case("[\u{c}1]", 1)]
+    #[case("[\u{a0}1]", 1)]
+    #[case("/* comment */ []", 0)]
+    fn reject(#[case] document: &str, #[case] offset: usize) {
+        match parse(document) {
+            Ok(value) => panic!("{:?} parsed as {:?}", document, value),
+            Err(error) => assert_eq!(error.offset, offset, "{:?}: {}", document, error),
+        }
+    }
+
+    #[rstest]
+    #[case("[1 2]", "expected `,` or `]` at byte 3")]
+    #[case(r#"{"a" 1}"#, "expected `:` at byte 5")]
+    #[case("[1.e3]", "expected digits at byte 3")]
+    #[case(r#"["\q"]"#, "expected valid escape at byte 2")]
+    #[case("[\"\u{1}\"]", "expected escaped control character at byte 2")]
+    #[case(r#"["\udc00"]"#, "expected valid code point at byte 2")]
+    #[case("[1] 2", "unexpected trailing input at byte 4")]
+    fn error_messages(#[case] document: &str, #[case] expected: &str) {
+        assert_eq!(parse(document).unwrap_err().to_string(), expected);
+    }
+}
@@ -0,0 +1,448 @@
+use std::{collections::HashMap, fmt::Write, fs, path::Path, str::FromStr};
+
+use serde::Deserialize;
+
+use crate::{
+    error::{Error, Result},
+    DAYS,
+};
+
+/// When and how a member earned a star, as in `completion_day_level`.
+#[derive(Debug, Deserialize, PartialEq, Eq, Clone, Copy)]
+pub struct Star {
+    pub get_star_ts: i64,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Eq, Clone)]
+pub struct Member {
+    pub id: u64,
+    pub name: Option<String>,
+    #[serde(default)]
+    pub local_score: u32,
+    #[serde(default)]
+    pub stars: u32,
+    /// Stars by day and then by part, both keyed as strings (`"1"`, `"2"`).
+    #[serde(default)]
+    pub completion_day_level: HashMap<String, HashMap<String, Star>>,
+}
+
+impl Member {
+    /// The member name, or the placeholder shown by the site for anonymous users.
+    pub fn display_name(&self) -> String {
+        match &self.name {
+            Some(name) => name.clone(),
+            None => format!("(anonymous user #{})", self.id),
+        }
+    }
+
+    /// The timestamp of the star of `part` on `day`, if earned.
+    pub fn star_ts(&self, day: u8, part: u8) -> Option<i64> {
+        self.completion_day_level
+            .get(&day.to_string())?
+            .get(&part.to_string())
+            .map(|star| star.get_star_ts)
+    }
+
+    /// Seconds between the first and the second star of `day`.
+    pub fn second_star_delta(&self, day: u8) -> Option<i64> {
+        Some(self.star_ts(day, 2)? - self.star_ts(day, 1)?)
+    }
+}
+
+/// A private leaderboard, as exported from its JSON API.
+#[derive(Debug, Deserialize, PartialEq, Eq, Clone)]
+pub struct Leaderboard {
+    pub event: String,
+    pub owner_id: u64,
+    /// Members keyed by their id.
+    pub members: HashMap<String, Member>,
+}
+
+impl FromStr for Leaderboard {
+    type Err = serde_json::Error;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        serde_json::from_str(s)
+    }
+}
+
+/// A member's place on a single day: both star timestamps and the delta.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct DayRank<'a> {
+    pub member: &'a Member,
+    pub part1: Option<i64>,
+    pub part2: Option<i64>,
+}
+
+impl DayRank<'_> {
+    pub fn delta(&self) -> Option<i64> {
+        Some(self.part2? - self.part1?)
+    }
+}
+
+impl Leaderboard {
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path).map_err(|source| Error::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        content.parse().map_err(|source| Error::InvalidLeaderboard {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+
+    /// Members sorted by local score, best first, ties broken by id.
+    pub fn members(&self) -> Vec<&Member> {
+        let mut members: Vec<_> = self.members.values().collect();
+        members.sort_by_key(|m| (std::cmp::Reverse(m.local_score), m.id));
+        members
+    }
+
+    /// Members with at least one star on `day`, ranked by their second star,
+    /// then by their first one. Members without the second star come last.
+    pub fn day_rankings(&self, day: u8) -> Vec<DayRank<'_>> {
+        let mut ranks: Vec<_> = self
+            .members
+            .values()
+            .map(|member| DayRank {
+                member,
+                part1: member.star_ts(day, 1),
+                part2: member.star_ts(day, 2),
+            })
+            .filter(|rank| rank.part1.is_some())
+            .collect();
+        ranks.sort_by_key(|rank| (rank.part2.unwrap_or(i64::MAX), rank.part1, rank.member.id));
+        ranks
+    }
+
+    /// The local score of every member after each day, computed the way the
+    /// site does: for each star, the first member to get it earns as many
+    /// points as there are members, the second one less, and so on.
+    pub fn score_curves(&self) -> HashMap<u64, [u32; DAYS as usize]> {
+        let n = self.members.len() as u32;
+        let mut daily: HashMap<u64, [u32; DAYS as usize]> = self
+            .members
+            .values()
+            .map(|m| (m.id, [0; DAYS as usize]))
+            .collect();
+
+        for day in 1..=DAYS {
+            for part in 1..=2 {
+                let mut finishers: Vec<_> = self
+                    .members
+                    .values()
+                    .filter_map(|m| Some((m.star_ts(day, part)?, m.id)))
+                    .collect();
+                finishers.sort();
+
+                for (rank, (_, id)) in finishers.into_iter().enumerate() {
+                    daily.get_mut(&id).unwrap()[day as usize - 1] += n - rank as u32;
+                }
+            }
+        }
+
+        for scores in daily.values_mut() {
+            for day in 1..DAYS as usize {
+                scores[day] += scores[day - 1];
+            }
+        }
+
+        daily
+    }
+
+    /// The overall standings: rank, name, local score and stars.
+    pub fn standings_table(&self) -> Table {
+        let mut table = Table::new(["Rank", "Name", "Score", "Stars"]);
+        for (i, member) in self.members().into_iter().enumerate() {
+            table.push([
+                (i + 1).to_string(),
+                member.display_name(),
+                member.local_score.to_string(),
+                member.stars.to_string(),
+            ]);
+        }
+        table
+    }
+
+    /// The ranking of a single day, with the time taken for the second star.
+    pub fn day_table(&self, day: u8) -> Table {
+        let mut table = Table::new(["Rank", "Name", "Part 1", "Part 2", "Delta"]);
+        for (i, rank) in self.day_rankings(day).into_iter().enumerate() {
+            table.push([
+                (i + 1).to_string(),
+                rank.member.display_name(),
+                rank.part1.map(|ts| ts.to_string()).unwrap_or_default(),
+                rank.part2.map(|ts| ts.to_string()).unwrap_or_default(),
+                rank.delta().map(format_duration).unwrap_or_default(),
+            ]);
+        }
+        table
+    }
+
+    /// The time to the second star of every member on every day.
+    pub fn deltas_table(&self) -> Table {
+        let mut table = Table::new(day_headers());
+        for member in self.members() {
+            let mut row = vec![member.display_name()];
+            row.extend((1..=DAYS).map(|day| {
+                member
+                    .second_star_delta(day)
+                    .map(format_duration)
+                    .unwrap_or_default()
+            }));
+            table.push(row);
+        }
+        table
+    }
+
+    /// The cumulative local score of every member after each day.
+    pub fn curves_table(&self) -> Table {
+        let curves = self.score_curves();
+        let mut table = Table::new(day_headers());
+        for member in self.members() {
+            let mut row = vec![member.display_name()];
+            row.extend(curves[&member.id].iter().map(u32::to_string));
+            table.push(row);
+        }
+        table
+    }
+}
+
+fn day_headers() -> Vec<String> {
+    let mut headers = vec!["Name".to_string()];
+    headers.extend((1..=DAYS).map(|day| day.to_string()));
+    headers
+}
+
+/// Formats seconds as `h:mm:ss`.
+pub fn format_duration(seconds: i64) -> String {
+    let sign = if seconds < 0 { "-" } else { "" };
+    let seconds = seconds.abs();
+    format!(
+        "{}{}:{:02}:{:02}",
+        sign,
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+/// A plain table of strings, rendered either for the terminal or as CSV.
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct Table {
+    pub headers: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    pub fn new<S: ToString>(headers: impl IntoIterator<Item = S>) -> Self {
+        Self {
+            headers: headers.into_iter().map(|h| h.to_string()).collect(),
+            rows: Vec::new(),
+        }
+    }
+
+    pub fn push<S: ToString>(&mut self, row: impl IntoIterator<Item = S>) {
+        self.rows
+            .push(row.into_iter().map(|c| c.to_string()).collect());
+    }
+
+    /// Renders the table with aligned columns and a separator under the headers.
+    pub fn to_terminal(&self) -> String {
+        let mut widths: Vec<_> = self.headers.iter().map(|h| h.chars().count()).collect();
+        for row in &self.rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+
+        let mut out = String::new();
+        let mut write_row = |cells: &[String]| {
+            let line = cells
+                .iter()
+                .zip(&widths)
+                .map(|(cell, &width)| format!("{:width$}", cell, width = width))
+                .collect::<Vec<_>>()
+                .join(" | ");
+            writeln!(out, "{}", line.trim_end()).unwrap();
+        };
+
+        write_row(&self.headers);
+        write_row(&widths.iter().map(|&w| "-".repeat(w)).collect::<Vec<_>>());
+        for row in &self.rows {
+            write_row(row);
+        }
+        out
+    }
+
+    /// Renders the table as CSV, quoting cells when needed.
+    pub fn to_csv(&self) -> String {
+        let mut out = String::new();
+        for row in std::iter::once(&self.headers).chain(&self.rows) {
+            let line = row
+                .iter()
+                .map(|c| csv_cell(c))
+                .collect::<Vec<_>>()
+                .join(",");
+            writeln!(out, "{}", line).unwrap();
+        }
+        out
+    }
+}
+
+fn csv_cell(cell: &str) -> String {
+    if cell.contains([',', '"', '\n']) {
+        format!("\"{}\"", cell.replace('"', "\"\""))
+    } else {
+        cell.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    const LEADERBOARD: &str = r#"{
+        "event": "2015",
+        "owner_id": 1,
+        "members": {
+            "1": {
+                "id": 1, "name": "alice", "local_score": 8, "stars": 3, "global_score": 0,
+                "last_star_ts": 1449000300,
+                "completion_day_level": {
+                    "1": {
+                        "1": { "get_star_ts": 1449000100, "star_index": 1 },
+                        "2": { "get_star_ts": 1449000300, "star_index": 3 }
+                    },
+                    "2": { "1": { "get_star_ts": 1449090000, "star_index": 5 } }
+                }
+            },
+            "2": {
+                "id": 2, "name": null, "local_score": 5, "stars": 2, "global_score": 0,
+                "last_star_ts": 1449000250,
+                "completion_day_level": {
+                    "1": {
+                        "1": { "get_star_ts": 1449000200, "star_index": 2 },
+                        "2": { "get_star_ts": 1449000250, "star_index": 4 }
+                    }
+                }
+            },
+            "3": {
+                "id": 3, "name": "carol", "local_score": 0, "stars": 0, "global_score": 0,
+                "last_star_ts": 0, "completion_day_level": {}
+            }
+        }
+    }"#;
+
+    fn leaderboard() -> Leaderboard {
+        LEADERBOARD.parse().unwrap()
+    }
+
+    #[test]
+    fn parse_members() {
+        let leaderboard = leaderboard();
+
+        assert_eq!(leaderboard.event, "2015");
+        assert_eq!(leaderboard.members.len(), 3);
+        assert_eq!(
+            leaderboard.members["2"].display_name(),
+            "(anonymous user #2)"
+        );
+        assert_eq!(leaderboard.members["1"].star_ts(1, 2), Some(1449000300));
+        assert_eq!(leaderboard.members["1"].star_ts(2, 2), None);
+    }
+
+    #[test]
+    fn parse_invalid() {
+        assert!("{\"event\": 2015}".parse::<Leaderboard>().is_err());
+    }
+
+    #[test]
+    fn members_by_score() {
+        let leaderboard = leaderboard();
+        let ids: Vec<_> = leaderboard.members().iter().map(|m| m.id).collect();
+
+        assert_eq!(ids, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn day_rankings_by_second_star() {
+        let leaderboard = leaderboard();
+        let ranks = leaderboard.day_rankings(1);
+
+        let ids: Vec<_> = ranks.iter().map(|r| r.member.id).collect();
+        assert_eq!(ids, vec![2, 1]);
+        assert_eq!(ranks[0].delta(), Some(50));
+        assert_eq!(ranks[1].delta(), Some(200));
+
+        let ranks = leaderboard.day_rankings(2);
+        assert_eq!(ranks.len(), 1);
+        assert_eq!(ranks[0].delta(), None);
+    }
+
+    #[test]
+    fn score_curves_match_local_score() {
+        let leaderboard = leaderboard();
+        let curves = leaderboard.score_curves();
+
+        // Day 1: alice 3 + 2, anonymous 2 + 3. Day 2: alice 3.
+        assert_eq!(curves[&1][0], 5);
+        assert_eq!(curves[&1][1], 8);
+        assert_eq!(curves[&1][24], 8);
+        assert_eq!(curves[&2][24], 5);
+        assert_eq!(curves[&3][24], 0);
+
+        for member in leaderboard.members() {
+            assert_eq!(curves[&member.id][24], member.local_score, "{}", member.id);
+        }
+    }
+
+    #[test]
+    fn format_durations() {
+        assert_eq!(format_duration(50), "0:00:50");
+        assert_eq!(format_duration(3725), "1:02:05");
+        assert_eq!(format_duration(-61), "-0:01:01");
+    }
+
+    #[test]
+    fn render_terminal_table() {
+        let table = leaderboard().standings_table();
+
+        assert_eq!(
+            table.to_terminal(),
+            "\
+Rank | Name                | Score | Stars
+---- | ------------------- | ----- | -----
+1    | alice               | 8     | 3
+2    | (anonymous user #2) | 5     | 2
+3    | carol               | 0     | 0
+"
+        );
+    }
+
+    #[test]
+    fn render_csv() {
+        let mut table = Table::new(["Name", "Delta"]);
+        table.push(["alice", "0:00:50"]);
+        table.push(["bob, \"the\" builder", ""]);
+
+        assert_eq!(
+            table.to_csv(),
+            "Name,Delta\nalice,0:00:50\n\"bob, \"\"the\"\" builder\",\n"
+        );
+    }
+
+    #[test]
+    fn render_deltas() {
+        let table = leaderboard().deltas_table();
+
+        assert_eq!(table.headers.len(), 26);
+        assert_eq!(table.rows[0][0], "alice");
+        assert_eq!(table.rows[0][1], "0:03:20");
+        assert_eq!(table.rows[0][2], "");
+    }
+}
@@ -11,8 +11,36 @@ mod day7;
 mod day8;
 mod day9;
 
+pub mod automaton;
+pub mod bitgrid;
+pub mod cache;
+pub mod calendar;
+pub mod codec;
+pub mod combinatorics;
+pub mod error;
+pub mod geometry;
+pub mod graph;
+pub mod grid;
 pub mod input;
+pub mod interner;
+pub mod iter;
+pub mod json;
+pub mod leaderboard;
+pub mod memo;
+pub mod miner;
+pub mod numtheory;
+pub mod parse;
+pub mod registry;
+pub mod rle;
+pub mod rules;
+pub mod search;
 pub mod set;
+pub mod trace;
+pub mod tui;
+pub mod vm;
+
+/// The number of puzzles in a year.
+pub const DAYS: u8 = 25;
 
 // Only for doc testing, 'cause why not?
 pub use day8::Encode;
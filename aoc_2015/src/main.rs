@@ -1,3 +1,202 @@
-use aoc_runner_derive::aoc_main;
+use std::{env, process::ExitCode};
 
-aoc_main! { lib = aoc_2015; }
+use aoc_2015::{
+    cache::Cache,
+    calendar::Calendar,
+    input::{self, NamedInput},
+    leaderboard::Leaderboard,
+    registry::{Solution, SOLUTIONS},
+    tui, YEAR,
+};
+
+/// Usage:
+///
+/// - `aoc_2015 [DAY...] [--no-cache]` runs every solution against every input
+///   of its day, optionally restricted to the given days (`6` or `day6`).
+///   Results are cached unless `--no-cache` forces them to be recomputed.
+/// - `aoc_2015 leaderboard FILE [standings|deltas|curves|day N] [--csv]` shows
+///   a private leaderboard exported as JSON.
+/// - `aoc_2015 calendar [--runs N] [--no-color]` shows the stars and status of
+///   every day, timing each day `N` times (3 by default, 0 to skip).
+/// - `aoc_2015 tui` opens an interactive dashboard to run and inspect solutions.
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    match args.first().map(String::as_str) {
+        Some("leaderboard") => leaderboard(&args[1..]),
+        Some("calendar") => calendar(&args[1..]),
+        Some("tui") => match tui::run() {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(e) => {
+                eprintln!("{}", e);
+                ExitCode::FAILURE
+            }
+        },
+        _ => run_days(&args),
+    }
+}
+
+fn run_days(args: &[String]) -> ExitCode {
+    let cache = if args.iter().any(|arg| arg == "--no-cache") {
+        None
+    } else {
+        Some(Cache::new(Cache::default_dir()))
+    };
+
+    let days: Vec<u8> = args
+        .iter()
+        .filter(|arg| *arg != "--no-cache")
+        .map(|arg| {
+            arg.trim_start_matches("day")
+                .parse()
+                .unwrap_or_else(|_| panic!("Invalid day: {}", arg))
+        })
+        .collect();
+
+    println!("Advent of code {}", YEAR);
+
+    let mut failed = false;
+    for solution in SOLUTIONS {
+        if !days.is_empty() && !days.contains(&solution.day) {
+            continue;
+        }
+
+        let inputs = match input::inputs(&solution.day_name()) {
+            Ok(inputs) => inputs,
+            Err(e) => {
+                eprintln!("{}: {}", solution, e);
+                failed = true;
+                continue;
+            }
+        };
+
+        for input in &inputs {
+            failed |= !run(solution, input, cache.as_ref());
+        }
+    }
+
+    if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+fn calendar(args: &[String]) -> ExitCode {
+    let mut runs = 3;
+    let mut color = true;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--no-color" => color = false,
+            "--runs" => match args.next().and_then(|n| n.parse().ok()) {
+                Some(n) => runs = n,
+                None => {
+                    eprintln!("Usage: calendar [--runs N] [--no-color]");
+                    return ExitCode::FAILURE;
+                }
+            },
+            arg => {
+                eprintln!("Unknown argument: {}", arg);
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+
+    let result = Calendar::load(YEAR).and_then(|mut calendar| {
+        calendar.measure(runs)?;
+        Ok(calendar)
+    });
+
+    match result {
+        Ok(calendar) => {
+            print!("{}", calendar.render(color));
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn leaderboard(args: &[String]) -> ExitCode {
+    let csv = args.iter().any(|arg| arg == "--csv");
+    let args: Vec<_> = args
+        .iter()
+        .filter(|arg| *arg != "--csv")
+        .map(String::as_str)
+        .collect();
+
+    let Some(path) = args.first() else {
+        eprintln!("Usage: leaderboard FILE [standings|deltas|curves|day N] [--csv]");
+        return ExitCode::FAILURE;
+    };
+
+    let leaderboard = match Leaderboard::from_file(path) {
+        Ok(leaderboard) => leaderboard,
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let table = match args[1..] {
+        [] | ["standings"] => leaderboard.standings_table(),
+        ["deltas"] => leaderboard.deltas_table(),
+        ["curves"] => leaderboard.curves_table(),
+        ["day", day] => match day.parse() {
+            Ok(day) => leaderboard.day_table(day),
+            Err(_) => {
+                eprintln!("Invalid day: {}", day);
+                return ExitCode::FAILURE;
+            }
+        },
+        ref view => {
+            eprintln!("Unknown view: {}", view.join(" "));
+            return ExitCode::FAILURE;
+        }
+    };
+
+    if csv {
+        print!("{}", table.to_csv());
+    } else {
+        print!("{}", table.to_terminal());
+    }
+
+    ExitCode::SUCCESS
+}
+
+/// Runs a solution against a single input, or takes its result from the
+/// cache, and reports the outcome, returning whether it did not fail.
+fn run(solution: &Solution, input: &NamedInput, cache: Option<&Cache>) -> bool {
+    let title = format!("{} [{}]", solution, input.name);
+
+    let result = match cache {
+        Some(cache) => cache.run(solution, &input.text),
+        None => solution.run(&input.text).map(|outcome| (outcome, false)),
+    };
+    let (outcome, cached) = match result {
+        Ok(result) => result,
+        Err(e) => {
+            eprintln!("{}: {}\n", title, e);
+            return false;
+        }
+    };
+
+    let (mut status, ok) = match input.answers.get(solution.part) {
+        Some(expected) if expected == outcome.answer => ("ok".to_string(), true),
+        Some(expected) => (format!("WRONG, expected {}", expected), false),
+        None => ("unchecked".to_string(), true),
+    };
+    if cached {
+        status.push_str(", cached");
+    }
+
+    println!(
+        "{}: {} ({})\n\tgenerator: {:?},\n\trunner: {:?}\n",
+        title, outcome.answer, status, outcome.generator, outcome.runner
+    );
+
+    ok
+}
@@ -0,0 +1,300 @@
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    fmt,
+    hash::Hash,
+};
+
+/// The error of a memoized function that needs the value of another key first.
+///
+/// Returned by [`Resolver::get`] so that `?` hands the request back to
+/// [`Memo::get`], which computes the key and calls the function again.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Need<K>(pub K);
+
+/// The keys of a computation that depends on itself, each once in the order
+/// they asked for each other.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Cycle<K>(pub Vec<K>);
+
+impl<K: fmt::Display> fmt::Display for Cycle<K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "cycle: ")?;
+        for key in &self.0 {
+            write!(f, "{} -> ", key)?;
+        }
+        match self.0.first() {
+            Some(first) => write!(f, "{}", first),
+            None => Ok(()),
+        }
+    }
+}
+
+/// How a [`Memo`] was used since it was created.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub struct Stats {
+    /// Values found already computed.
+    pub hits: usize,
+    /// Values computed.
+    pub misses: usize,
+    /// Values dropped to stay within the bound.
+    pub evictions: usize,
+}
+
+/// Gives a memoized function the values of the keys it depends on.
+pub struct Resolver<'a, K, V> {
+    values: &'a HashMap<K, V>,
+    hits: usize,
+}
+
+impl<K: Eq + Hash + Clone, V: Clone> Resolver<'_, K, V> {
+    /// The value of `key`, or a [`Need`] to return with `?` when it is not
+    /// computed yet.
+    pub fn get(&mut self, key: &K) -> Result<V, Need<K>> {
+        match self.values.get(key) {
+            Some(value) => {
+                self.hits += 1;
+                Ok(value.clone())
+            }
+            None => Err(Need(key.clone())),
+        }
+    }
+}
+
+/// Memoizes a recursive function without recursing on the native stack.
+///
+/// The function is written as a step: given a key, it either returns the
+/// value, or asks for the value of another key with [`Resolver::get`] and `?`.
+/// [`Memo::get`] then computes that key on its own stack and calls the
+/// function again, so the function should be cheap until it has every value
+/// it needs.
+///
+/// # Examples
+///
+/// ```rust
+/// use aoc_2015::memo::Memo;
+///
+/// let mut fibonacci = Memo::new();
+/// let value = fibonacci.get(90u64, |&n, memo| {
+///     Ok(if n < 2 { n } else { memo.get(&(n - 1))? + memo.get(&(n - 2))? })
+/// });
+///
+/// assert_eq!(value, Ok(2_880_067_194_370_816_120));
+/// assert_eq!(fibonacci.stats().misses, 91);
+/// ```
+pub struct Memo<K, V> {
+    values: HashMap<K, V>,
+    /// The keys in the order they were computed, to evict the oldest first.
+    order: VecDeque<K>,
+    bound: Option<usize>,
+    stats: Stats,
+}
+
+impl<K, V> Default for Memo<K, V> {
+    fn default() -> Self {
+        Self {
+            values: HashMap::new(),
+            order: VecDeque::new(),
+            bound: None,
+            stats: Stats::default(),
+        }
+    }
+}
+
+impl<K: Eq + Hash + Clone, V: Clone> Memo<K, V> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A memo keeping at most `bound` values between calls to [`Memo::get`],
+    /// dropping the oldest ones first. A single call keeps every value it
+    /// computes until it returns, as they may be needed again.
+    pub fn bounded(bound: usize) -> Self {
+        Self {
+            bound: Some(bound),
+            ..Self::default()
+        }
+    }
+
+    pub fn stats(&self) -> Stats {
+        self.stats
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.values.clear();
+        self.order.clear();
+    }
+
+    /// The value of `f` for `key`, computing it and the keys it depends on as
+    /// needed, or the cycle found among them.
+    pub fn get<F>(&mut self, key: K, mut f: F) -> Result<V, Cycle<K>>
+    where
+        F: FnMut(&K, &mut Resolver<'_, K, V>) -> Result<V, Need<K>>,
+    {
+        if let Some(value) = self.values.get(&key) {
+            self.stats.hits += 1;
+            return Ok(value.clone());
+        }
+
+        let mut stack = vec![key.clone()];
+        let mut pending = HashSet::from([key.clone()]);
+        while let Some(current) = stack.last() {
+            let mut resolver = Resolver {
+                values: &self.values,
+                hits: 0,
+            };
+            let step = f(current, &mut resolver);
+            self.stats.hits += resolver.hits;
+
+            match step {
+                Ok(value) => {
+                    let current = stack.pop().unwrap();
+                    pending.remove(&current);
+                    self.stats.misses += 1;
+                    self.order.push_back(current.clone());
+                    self.values.insert(current, value);
+                }
+                Err(Need(dependency)) => {
+                    if pending.contains(&dependency) {
+                        let start = stack.iter().position(|k| *k == dependency).unwrap();
+                        return Err(Cycle(stack.split_off(start)));
+                    }
+                    pending.insert(dependency.clone());
+                    stack.push(dependency);
+                }
+            }
+        }
+
+        let value = self.values[&key].clone();
+        self.evict();
+        Ok(value)
+    }
+
+    fn evict(&mut self) {
+        let Some(bound) = self.bound else {
+            return;
+        };
+        while self.values.len() > bound {
+            let Some(oldest) = self.order.pop_front() else {
+                break;
+            };
+            self.values.remove(&oldest);
+            self.stats.evictions += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn collatz_steps(memo: &mut Memo<u64, u32>, n: u64) -> u32 {
+        memo.get(n, |&n, memo| {
+            Ok(match n {
+                1 => 0,
+                n if n % 2 == 0 => memo.get(&(n / 2))? + 1,
+                n => memo.get(&(3 * n + 1))? + 1,
+            })
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn deep_recursion_does_not_overflow() {
+        let mut memo = Memo::new();
+        let value = memo.get(1_000_000u64, |&n, memo| {
+            Ok(if n == 0 { 0 } else { memo.get(&(n - 1))? + 1 })
+        });
+
+        assert_eq!(value, Ok(1_000_000u64));
+    }
+
+    #[test]
+    fn reuses_values_across_calls() {
+        let mut memo = Memo::new();
+
+        assert_eq!(collatz_steps(&mut memo, 27), 111);
+        let computed = memo.stats().misses;
+        assert_eq!(computed, 112);
+
+        // 82 comes right after 27, so every value it needs is known.
+        assert_eq!(collatz_steps(&mut memo, 82), 110);
+        assert_eq!(memo.stats().misses, computed);
+        assert_eq!(collatz_steps(&mut memo, 54), 112);
+        assert_eq!(memo.stats().misses, computed + 1);
+        assert_eq!(memo.len(), computed + 1);
+    }
+
+    #[test]
+    fn counts_hits() {
+        let mut memo = Memo::new();
+        memo.get(5u64, |&n, memo| {
+            Ok(if n < 2 {
+                n
+            } else {
+                memo.get(&(n - 1))? + memo.get(&(n - 2))?
+            })
+        })
+        .unwrap();
+
+        // Each of 2..=5 finds both its values once they are computed, and 2
+        // also found 1 before asking for 0.
+        assert_eq!(
+            memo.stats(),
+            Stats {
+                hits: 9,
+                misses: 6,
+                evictions: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn bounded_size() {
+        let mut memo = Memo::bounded(10);
+
+        assert_eq!(collatz_steps(&mut memo, 27), 111);
+        assert_eq!(memo.len(), 10);
+        assert_eq!(memo.stats().evictions, 102);
+
+        // The newest values are kept: those computed last, from the start of
+        // the sequence.
+        assert_eq!(collatz_steps(&mut memo, 82), 110);
+        assert_eq!(memo.stats().misses, 112);
+        assert_eq!(collatz_steps(&mut memo, 4), 2);
+        assert_eq!(memo.stats().misses, 115);
+
+        memo.clear();
+        assert!(memo.is_empty());
+    }
+
+    #[test]
+    fn detects_cycles() {
+        let next = HashMap::from([('a', 'b'), ('b', 'c'), ('c', 'd'), ('d', 'b')]);
+        let mut memo: Memo<char, u32> = Memo::new();
+
+        let cycle = memo
+            .get('a', |key, memo| Ok(memo.get(&next[key])? + 1))
+            .unwrap_err();
+
+        assert_eq!(cycle, Cycle(vec!['b', 'c', 'd']));
+        assert_eq!(cycle.to_string(), "cycle: b -> c -> d -> b");
+    }
+
+    #[test]
+    fn detects_self_dependency() {
+        let mut memo: Memo<u8, u8> = Memo::new();
+        let cycle = memo.get(1, |&key, memo| memo.get(&key)).unwrap_err();
+
+        assert_eq!(cycle, Cycle(vec![1]));
+    }
+}
@@ -0,0 +1,255 @@
+//! Mining MD5 digests: the smallest number which, appended to a key, gives a
+//! digest passing a test, like starting with five zeros in hex.
+//!
+//! Day 4 is the first of these puzzles, and 2016 has more, looking for
+//! digests by their hex digits: [`leading_zero_nibbles`] and [`nibble`] read
+//! them straight from the bytes.
+
+use std::ops::Range;
+
+use rayon::prelude::*;
+
+/// The indices one thread hashes in a row before the threads compare notes.
+const DEFAULT_CHUNK_SIZE: u64 = 4096;
+
+/// Looks for the smallest index whose MD5 digest, of a key followed by the
+/// index in decimal, passes a test.
+///
+/// The key is hashed once and the state cloned for each index; each thread
+/// increments the digits of its index in place; and digests are tested on
+/// their raw bytes, never formatted as hex. The threads scan consecutive
+/// chunks of indices, and a batch of chunks only ends the search once every
+/// chunk of it is done, so the smallest match wins.
+///
+/// # Examples
+///
+/// ```rust
+/// use aoc_2015::miner::{leading_zero_nibbles, Miner};
+///
+/// let miner = Miner::new("abcdef");
+/// assert_eq!(miner.find_zeros(5), Some(609043));
+///
+/// let digest = miner.digest(609043);
+/// assert_eq!(leading_zero_nibbles(&digest), 5);
+/// assert_eq!(format!("{:x}", md5::Digest(digest)), "000001dbbfa3a5c83a2d506429c7b00e");
+/// ```
+#[derive(Clone)]
+pub struct Miner {
+    key: md5::Context,
+    chunk_size: u64,
+}
+
+impl Miner {
+    /// A miner appending indices to `key`, in chunks of the default size.
+    pub fn new(key: impl AsRef<[u8]>) -> Self {
+        let mut context = md5::Context::new();
+        context.consume(key);
+        Self {
+            key: context,
+            chunk_size: DEFAULT_CHUNK_SIZE,
+        }
+    }
+
+    /// Sets how many indices a thread scans at once. Smaller chunks waste
+    /// less work past the match, larger ones synchronize less often.
+    ///
+    /// # Panics
+    ///
+    /// If `size` is 0.
+    pub fn chunk_size(mut self, size: u64) -> Self {
+        assert!(size > 0, "chunk size must be positive");
+        self.chunk_size = size;
+        self
+    }
+
+    /// The digest of the key followed by `index`.
+    pub fn digest(&self, index: u64) -> [u8; 16] {
+        let mut context = self.key.clone();
+        context.consume(index.to_string());
+        context.compute().0
+    }
+
+    /// The smallest index in `indices` whose digest passes `accept`.
+    pub fn find<F>(&self, indices: Range<u64>, accept: F) -> Option<u64>
+    where
+        F: Fn(&[u8; 16]) -> bool + Sync,
+    {
+        let batch = self.chunk_size * rayon::current_num_threads() as u64;
+        let mut start = indices.start;
+        while start < indices.end {
+            let end = start.saturating_add(batch).min(indices.end);
+            let chunks = (end - start).div_ceil(self.chunk_size);
+            let found = (0..chunks)
+                .into_par_iter()
+                .filter_map(|chunk| {
+                    let from = start + chunk * self.chunk_size;
+                    let to = from.saturating_add(self.chunk_size).min(end);
+                    self.scan(from..to, &accept)
+                })
+                .min();
+            if found.is_some() {
+                return found;
+            }
+            start = end;
+        }
+        None
+    }
+
+    /// The smallest index whose digest starts with `zeros` zeros in hex.
+    pub fn find_zeros(&self, zeros: u32) -> Option<u64> {
+        self.find(0..u64::MAX, |digest| leading_zero_nibbles(digest) >= zeros)
+    }
+
+    /// The first index of `indices` whose digest passes `accept`, on this
+    /// thread.
+    fn scan(&self, indices: Range<u64>, accept: impl Fn(&[u8; 16]) -> bool) -> Option<u64> {
+        let mut counter = Counter::new(indices.start);
+        for index in indices {
+            let mut context = self.key.clone();
+            context.consume(counter.digits());
+            if accept(&context.compute().0) {
+                return Some(index);
+            }
+            counter.increment();
+        }
+        None
+    }
+}
+
+/// The number of hex digits a digest starts with that are 0.
+pub fn leading_zero_nibbles(digest: &[u8; 16]) -> u32 {
+    u128::from_be_bytes(*digest).leading_zeros() / 4
+}
+
+/// The hex digit at `position` in a digest, counting from 0.
+pub fn nibble(digest: &[u8; 16], position: usize) -> u8 {
+    let byte = digest[position / 2];
+    if position.is_multiple_of(2) {
+        byte >> 4
+    } else {
+        byte & 0xf
+    }
+}
+
+/// The decimal digits of a number, right-aligned in a buffer so that carrying
+/// into a new digit only writes before them.
+struct Counter {
+    buffer: [u8; 20],
+    len: usize,
+}
+
+impl Counter {
+    fn new(value: u64) -> Self {
+        let digits = value.to_string();
+        let mut buffer = [b'0'; 20];
+        buffer[20 - digits.len()..].copy_from_slice(digits.as_bytes());
+        Self {
+            buffer,
+            len: digits.len(),
+        }
+    }
+
+    fn digits(&self) -> &[u8] {
+        &self.buffer[20 - self.len..]
+    }
+
+    fn increment(&mut self) {
+        for digit in self.buffer[20 - self.len..].iter_mut().rev() {
+            if *digit == b'9' {
+                *digit = b'0';
+            } else {
+                *digit += 1;
+                return;
+            }
+        }
+        // Every digit was a 9: they are all 0 now, after a new leading 1.
+        // Past u64::MAX, the buffer is full and the counter is done anyway.
+        if self.len < self.buffer.len() {
+            self.len += 1;
+            self.buffer[20 - self.len] = b'1';
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case(0)]
+    #[case(8)]
+    #[case(9)]
+    #[case(99)]
+    #[case(1_099_999)]
+    fn counter_carries(#[case] start: u64) {
+        let mut counter = Counter::new(start);
+        for value in start..start + 12 {
+            assert_eq!(counter.digits(), value.to_string().as_bytes());
+            counter.increment();
+        }
+    }
+
+    #[rstest]
+    #[case("00000a", 5)]
+    #[case("0000000000000000000000000000000f", 31)]
+    #[case("00000000000000000000000000000000", 32)]
+    #[case("10000000000000000000000000000000", 0)]
+    #[case("01000000000000000000000000000000", 1)]
+    fn zero_nibbles(#[case] hex: &str, #[case] expected: u32) {
+        let hex = format!("{:0<32}", hex);
+        let digest = u128::from_str_radix(&hex, 16).unwrap().to_be_bytes();
+        assert_eq!(leading_zero_nibbles(&digest), expected);
+    }
+
+    #[test]
+    fn nibbles_match_hex() {
+        let digest = md5::compute("abc").0;
+        let hex = format!("{:x}", md5::Digest(digest));
+        for (position, c) in hex.chars().enumerate() {
+            assert_eq!(nibble(&digest, position), c.to_digit(16).unwrap() as u8);
+        }
+    }
+
+    /// The first index found the slow way, formatting each digest.
+    fn naive_find(key: &str, zeros: usize, indices: Range<u64>) -> Option<u64> {
+        let prefix = "0".repeat(zeros);
+        indices
+            .into_iter()
+            .find(|i| format!("{:x}", md5::compute(format!("{}{}", key, i))).starts_with(&prefix))
+    }
+
+    #[rstest]
+    #[case(1)]
+    #[case(7)]
+    #[case(64)]
+    #[case(DEFAULT_CHUNK_SIZE)]
+    fn smallest_match_whatever_the_chunks(#[case] chunk_size: u64) {
+        let miner = Miner::new("xyz").chunk_size(chunk_size);
+        for start in [0, 5, 97, 1000] {
+            let indices = start..start + 3000;
+            assert_eq!(
+                miner.find(indices.clone(), |d| leading_zero_nibbles(d) >= 2),
+                naive_find("xyz", 2, indices),
+                "from {} in chunks of {}",
+                start,
+                chunk_size
+            );
+        }
+    }
+
+    #[test]
+    fn no_match_in_range() {
+        let miner = Miner::new("abcdef");
+        assert_eq!(miner.find(0..1000, |d| leading_zero_nibbles(d) >= 5), None);
+        assert_eq!(miner.find(10..10, |_| true), None);
+        assert_eq!(miner.find(10..20, |_| true), Some(10));
+    }
+
+    #[test]
+    fn example_part_one() {
+        assert_eq!(Miner::new("pqrstuv").find_zeros(5), Some(1048970));
+    }
+}
@@ -0,0 +1,315 @@
+//! Divisors, modular arithmetic and congruences.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use aoc_2015::numtheory::{crt, divisor_sums, modinv, modpow};
+//!
+//! assert_eq!(divisor_sums(6)[6], 1 + 2 + 3 + 6);
+//! assert_eq!(modpow(2, 10, 1000), 24);
+//! assert_eq!(modinv(3, 7), Some(5));
+//! assert_eq!(crt(&[(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+//! ```
+
+/// The greatest common divisor of `a` and `b`, with `gcd(0, 0) == 0`.
+pub fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// The least common multiple of `a` and `b`, with `lcm(0, n) == 0`.
+pub fn lcm(a: u64, b: u64) -> u64 {
+    if a == 0 || b == 0 {
+        0
+    } else {
+        a / gcd(a, b) * b
+    }
+}
+
+/// The gcd `g` of `a` and `b` along with the Bézout coefficients `x` and `y`
+/// such that `a * x + b * y == g`, as `(g, x, y)`. `g` is never negative.
+pub fn extended_gcd(a: i64, b: i64) -> (i64, i64, i64) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1, 0);
+    let (mut old_y, mut y) = (0, 1);
+    while r != 0 {
+        let quotient = old_r / r;
+        (old_r, r) = (r, old_r - quotient * r);
+        (old_x, x) = (x, old_x - quotient * x);
+        (old_y, y) = (y, old_y - quotient * y);
+    }
+    if old_r < 0 {
+        (-old_r, -old_x, -old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+/// `base` to the power `exp`, modulo `modulus`, by repeated squaring.
+///
+/// # Panics
+///
+/// If `modulus` is 0.
+pub fn modpow(base: u64, mut exp: u64, modulus: u64) -> u64 {
+    assert!(modulus > 0, "modulus must be positive");
+    let modulus = u128::from(modulus);
+    let mut base = u128::from(base) % modulus;
+    let mut result = 1 % modulus;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = result * base % modulus;
+        }
+        base = base * base % modulus;
+        exp >>= 1;
+    }
+    result as u64
+}
+
+/// The inverse of `a` modulo `modulus`, in `0..modulus`, if `a` and `modulus`
+/// are coprime.
+///
+/// # Panics
+///
+/// If `modulus` is not positive.
+pub fn modinv(a: i64, modulus: i64) -> Option<i64> {
+    assert!(modulus > 0, "modulus must be positive");
+    let (g, x, _) = extended_gcd(a.rem_euclid(modulus), modulus);
+    (g == 1).then(|| x.rem_euclid(modulus))
+}
+
+/// Solves the system of congruences `x ≡ residue (mod modulus)` given as
+/// `(residue, modulus)` pairs, with moduli that need not be coprime.
+///
+/// Returns the smallest non-negative solution with the modulus every solution
+/// is congruent under, or `None` if the congruences contradict each other or
+/// that modulus does not fit an `i64`.
+///
+/// # Panics
+///
+/// If a modulus is not positive.
+pub fn crt(congruences: &[(i64, i64)]) -> Option<(i64, i64)> {
+    let (mut residue, mut modulus) = (0i128, 1i128);
+    for &(r, m) in congruences {
+        assert!(m > 0, "modulus must be positive");
+        let (r, m) = (i128::from(r).rem_euclid(i128::from(m)), i128::from(m));
+
+        let g = gcd(modulus as u64, m as u64) as i128;
+        if (r - residue) % g != 0 {
+            return None;
+        }
+        // residue + modulus * k ≡ r (mod m), solved for k modulo m / g.
+        let step = m / g;
+        let inverse = modinv(((modulus / g) % step) as i64, step as i64)? as i128;
+        let k = ((r - residue) / g % step * inverse).rem_euclid(step);
+
+        residue += modulus * k;
+        modulus *= step;
+        if modulus > i128::from(i64::MAX) {
+            return None;
+        }
+    }
+    Some((residue as i64, modulus as i64))
+}
+
+/// The largest integer whose square is at most `n`.
+pub fn isqrt(n: u64) -> u64 {
+    if n < 2 {
+        return n;
+    }
+    // Newton's method decreases towards the root from any guess above it.
+    let mut x = 1 << (u64::BITS - n.leading_zeros()).div_ceil(2);
+    loop {
+        let y = (x + n / x) / 2;
+        if y >= x {
+            return x;
+        }
+        x = y;
+    }
+}
+
+/// The sum of the divisors of every number up to `limit`, indexed by number,
+/// with 0 for 0.
+pub fn divisor_sums(limit: usize) -> Vec<u64> {
+    bounded_divisor_sums(limit, usize::MAX)
+}
+
+/// Like [`divisor_sums`], but each divisor only counts for its first
+/// `multiples` multiples: `d` divides `n` and `n / d <= multiples`.
+pub fn bounded_divisor_sums(limit: usize, multiples: usize) -> Vec<u64> {
+    let mut sums = vec![0; limit + 1];
+    for divisor in 1..=limit {
+        for n in (divisor..=limit).step_by(divisor).take(multiples) {
+            sums[n] += divisor as u64;
+        }
+    }
+    sums
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::*;
+
+    // Naive versions to check against, exhaustively over small inputs.
+
+    fn naive_gcd(a: u64, b: u64) -> u64 {
+        (1..=a.max(b))
+            .rev()
+            .find(|&d| a.is_multiple_of(d) && b.is_multiple_of(d))
+            .unwrap_or(0)
+    }
+
+    fn naive_divisor_sum(n: usize, multiples: usize) -> u64 {
+        (1..=n)
+            .filter(|&d| n.is_multiple_of(d) && n / d <= multiples)
+            .sum::<usize>() as u64
+    }
+
+    #[test]
+    fn gcd_and_lcm_match_naive() {
+        for a in 0..60 {
+            for b in 0..60 {
+                let g = naive_gcd(a, b);
+                assert_eq!(gcd(a, b), g, "gcd({}, {})", a, b);
+                let l = (1..=a * b).find(|m| m.is_multiple_of(a) && m.is_multiple_of(b));
+                assert_eq!(lcm(a, b), l.unwrap_or(0), "lcm({}, {})", a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn extended_gcd_gives_bezout_coefficients() {
+        for a in -40i64..40 {
+            for b in -40i64..40 {
+                let (g, x, y) = extended_gcd(a, b);
+                assert_eq!(g as u64, naive_gcd(a.unsigned_abs(), b.unsigned_abs()));
+                assert_eq!(a * x + b * y, g, "extended_gcd({}, {})", a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn modpow_matches_naive() {
+        for modulus in 1..30u64 {
+            for base in 0..40 {
+                let mut naive = 1 % modulus;
+                for exp in 0..20 {
+                    assert_eq!(
+                        modpow(base, exp, modulus),
+                        naive,
+                        "{}^{} % {}",
+                        base,
+                        exp,
+                        modulus
+                    );
+                    naive = naive * base % modulus;
+                }
+            }
+        }
+    }
+
+    #[rstest]
+    #[case(2, 1_000_000_006, 1_000_000_007, 1)]
+    #[case(u64::MAX, u64::MAX, u64::MAX - 1, 1)]
+    #[case(252_533, 0, 33_554_393, 1)]
+    #[case(12, 3, 1, 0)]
+    fn modpow_large(
+        #[case] base: u64,
+        #[case] exp: u64,
+        #[case] modulus: u64,
+        #[case] expected: u64,
+    ) {
+        assert_eq!(modpow(base, exp, modulus), expected);
+    }
+
+    #[test]
+    fn modinv_matches_naive() {
+        for modulus in 1..80i64 {
+            for a in -80..80i64 {
+                let naive = (0..modulus).find(|x| (a * x).rem_euclid(modulus) == 1 % modulus);
+                assert_eq!(modinv(a, modulus), naive, "modinv({}, {})", a, modulus);
+            }
+        }
+    }
+
+    #[test]
+    fn crt_matches_naive() {
+        for m1 in 1..14i64 {
+            for m2 in 1..14i64 {
+                let l = lcm(m1 as u64, m2 as u64) as i64;
+                for r1 in 0..m1 {
+                    for r2 in 0..m2 {
+                        let naive = (0..l).find(|x| x % m1 == r1 && x % m2 == r2);
+                        assert_eq!(
+                            crt(&[(r1, m1), (r2, m2)]),
+                            naive.map(|x| (x, l)),
+                            "x = {} mod {}, x = {} mod {}",
+                            r1,
+                            m1,
+                            r2,
+                            m2
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[rstest]
+    #[case(&[], Some((0, 1)))]
+    #[case(&[(-1, 5)], Some((4, 5)))]
+    #[case(&[(0, 7), (12, 13), (55, 59), (25, 31), (12, 19)], Some((1_068_781, 3_162_341)))]
+    #[case(&[(1, 4), (2, 6)], None)]
+    #[case(&[(0, 1 << 40), (1, (1 << 40) - 1)], None)]
+    fn crt_systems(#[case] congruences: &[(i64, i64)], #[case] expected: Option<(i64, i64)>) {
+        assert_eq!(crt(congruences), expected);
+    }
+
+    #[test]
+    fn isqrt_matches_naive() {
+        let mut root = 0;
+        for n in 0..10_000u64 {
+            if (root + 1) * (root + 1) <= n {
+                root += 1;
+            }
+            assert_eq!(isqrt(n), root, "isqrt({})", n);
+        }
+    }
+
+    #[rstest]
+    #[case(u64::MAX, u32::MAX as u64)]
+    #[case(1 << 62, 1 << 31)]
+    #[case((1 << 62) - 1, (1 << 31) - 1)]
+    #[case(999_999_999_999_999_999, 999_999_999)]
+    fn isqrt_large(#[case] n: u64, #[case] expected: u64) {
+        assert_eq!(isqrt(n), expected);
+    }
+
+    #[test]
+    fn divisor_sums_match_naive() {
+        let sums = divisor_sums(2000);
+        for (n, &sum) in sums.iter().enumerate() {
+            assert_eq!(sum, naive_divisor_sum(n, usize::MAX), "sigma({})", n);
+        }
+    }
+
+    #[test]
+    fn bounded_divisor_sums_match_naive() {
+        for multiples in [1, 2, 7, 50] {
+            let sums = bounded_divisor_sums(1000, multiples);
+            for (n, &sum) in sums.iter().enumerate() {
+                assert_eq!(
+                    sum,
+                    naive_divisor_sum(n, multiples),
+                    "n = {}, {} multiples",
+                    n,
+                    multiples
+                );
+            }
+        }
+    }
+}
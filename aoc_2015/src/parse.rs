@@ -0,0 +1,282 @@
+use std::str::FromStr;
+
+use nom::{
+    branch::alt,
+    bytes::complete::tag,
+    character::complete::{alpha1, alphanumeric1, char, digit1, one_of, space0},
+    combinator::{all_consuming, map_res, opt, recognize},
+    error::{
+        ContextError, ErrorKind, FromExternalError, ParseError, VerboseError, VerboseErrorKind,
+    },
+    multi::many0_count,
+    sequence::{pair, separated_pair, tuple},
+    IResult,
+};
+
+use crate::error::{Error, Result};
+
+/// The result of a parser keeping the trail [`parse_all`] and [`parse_lines`]
+/// need to point at the failure.
+pub type ParseResult<'a, T> = IResult<&'a str, T, VerboseError<&'a str>>;
+
+/// An unsigned integer, like `42`.
+pub fn unsigned<'a, T, E>(input: &'a str) -> IResult<&'a str, T, E>
+where
+    T: FromStr,
+    E: ParseError<&'a str> + ContextError<&'a str> + FromExternalError<&'a str, T::Err>,
+{
+    nom::error::context("unsigned integer", map_res(digit1, str::parse))(input)
+}
+
+/// An integer with an optional sign, like `-42` or `+42`.
+pub fn signed<'a, T, E>(input: &'a str) -> IResult<&'a str, T, E>
+where
+    T: FromStr,
+    E: ParseError<&'a str> + ContextError<&'a str> + FromExternalError<&'a str, T::Err>,
+{
+    nom::error::context(
+        "integer",
+        map_res(recognize(pair(opt(one_of("+-")), digit1)), str::parse),
+    )(input)
+}
+
+/// Two integers separated by a comma, like `3,-4` or `3, -4`.
+pub fn point<'a, T, E>(input: &'a str) -> IResult<&'a str, (T, T), E>
+where
+    T: FromStr,
+    E: ParseError<&'a str> + ContextError<&'a str> + FromExternalError<&'a str, T::Err>,
+{
+    separated_pair(signed, pair(char(','), space0), signed)(input)
+}
+
+/// A name starting with a letter, followed by letters, digits and
+/// underscores.
+pub fn identifier<'a, E>(input: &'a str) -> IResult<&'a str, &'a str, E>
+where
+    E: ParseError<&'a str> + ContextError<&'a str>,
+{
+    nom::error::context(
+        "identifier",
+        recognize(pair(alpha1, many0_count(alt((alphanumeric1, tag("_")))))),
+    )(input)
+}
+
+/// A weighted edge between two identifiers, like `London to Dublin = 464`.
+pub fn route<'a, T, E>(input: &'a str) -> IResult<&'a str, (&'a str, &'a str, T), E>
+where
+    T: FromStr,
+    E: ParseError<&'a str> + ContextError<&'a str> + FromExternalError<&'a str, T::Err>,
+{
+    let (input, (from, _, to, _, value)) =
+        tuple((identifier, tag(" to "), identifier, tag(" = "), unsigned))(input)?;
+    Ok((input, (from, to, value)))
+}
+
+/// Runs `parser` on the whole of `input`.
+///
+/// # Examples
+///
+/// ```rust
+/// use aoc_2015::parse::{parse_all, route};
+///
+/// assert_eq!(parse_all("A to B = 5", route::<u32, _>).unwrap(), ("A", "B", 5));
+///
+/// let error = parse_all("A to B = x", route::<u32, _>).unwrap_err();
+/// assert_eq!(
+///     error.to_string(),
+///     "line 1, column 10: expected unsigned integer\nA to B = x\n         ^"
+/// );
+/// ```
+pub fn parse_all<'a, T, F>(input: &'a str, parser: F) -> Result<T>
+where
+    F: FnMut(&'a str) -> ParseResult<'a, T>,
+{
+    all_consuming(parser)(input)
+        .map(|(_, value)| value)
+        .map_err(|error| to_error(input, error))
+}
+
+/// Runs `parser` on each line of `input`, reporting the first failure with its
+/// position in `input`.
+pub fn parse_lines<'a, T, F>(input: &'a str, mut parser: F) -> Result<Vec<T>>
+where
+    F: FnMut(&'a str) -> ParseResult<'a, T>,
+{
+    input
+        .lines()
+        .enumerate()
+        .map(|(index, line)| {
+            parse_all(line, &mut parser).map_err(|error| match error {
+                Error::Parse {
+                    column,
+                    message,
+                    snippet,
+                    ..
+                } => Error::Parse {
+                    line: index + 1,
+                    column,
+                    message,
+                    snippet,
+                },
+                error => error,
+            })
+        })
+        .collect()
+}
+
+/// Turns the failure of a parser run on `input` into an [`Error::Parse`],
+/// pointing at the innermost position the parser gave up on.
+pub fn to_error(input: &str, error: nom::Err<VerboseError<&str>>) -> Error {
+    let (remaining, message) = match error {
+        nom::Err::Incomplete(_) => ("", "unexpected end of input".to_string()),
+        nom::Err::Error(error) | nom::Err::Failure(error) => describe(&error),
+    };
+
+    let offset = input.len() - remaining.len();
+    let before = &input[..offset];
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    let text = input[line_start..].lines().next().unwrap_or("");
+    let column = input[line_start..offset].chars().count() + 1;
+
+    Error::Parse {
+        line: before.matches('\n').count() + 1,
+        column,
+        message,
+        snippet: format!("{}\n{}^", text, " ".repeat(column - 1)),
+    }
+}
+
+/// Where the parser failed, and what it expected there: the context closest
+/// to the failure if there is one, as it names what was being parsed.
+pub(crate) fn describe<'a>(error: &VerboseError<&'a str>) -> (&'a str, String) {
+    let Some(&(remaining, ref innermost)) = error.errors.first() else {
+        return ("", "invalid input".to_string());
+    };
+
+    let context = error.errors.iter().find_map(|(at, kind)| match kind {
+        VerboseErrorKind::Context(context) if *at == remaining => Some(context),
+        _ => None,
+    });
+    let message = match (context, innermost) {
+        (Some(context), _) => format!("expected {}", context),
+        (None, VerboseErrorKind::Char(c)) => format!("expected '{}'", c),
+        (None, VerboseErrorKind::Nom(ErrorKind::Eof)) => "unexpected trailing input".to_string(),
+        (None, VerboseErrorKind::Nom(ErrorKind::Tag)) => {
+            format!(
+                "unexpected `{}`",
+                remaining
+                    .chars()
+                    .next()
+                    .map_or_else(String::new, String::from)
+            )
+        }
+        (None, VerboseErrorKind::Nom(kind)) => format!("invalid input ({})", kind.description()),
+        (None, VerboseErrorKind::Context(context)) => format!("expected {}", context),
+    };
+
+    (remaining, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case("42", 42)]
+    #[case("0", 0)]
+    fn parse_unsigned(#[case] input: &str, #[case] expected: u32) {
+        assert_eq!(parse_all(input, unsigned::<u32, _>).unwrap(), expected);
+    }
+
+    #[rstest]
+    #[case("42", 42)]
+    #[case("-42", -42)]
+    #[case("+7", 7)]
+    fn parse_signed(#[case] input: &str, #[case] expected: i64) {
+        assert_eq!(parse_all(input, signed::<i64, _>).unwrap(), expected);
+    }
+
+    #[test]
+    fn integers_out_of_range() {
+        assert!(parse_all("256", unsigned::<u8, _>).is_err());
+        assert!(parse_all("-1", signed::<u8, _>).is_err());
+        assert!(parse_all("-1", unsigned::<i8, _>).is_err());
+    }
+
+    #[rstest]
+    #[case("3,4", (3, 4))]
+    #[case("3, -4", (3, -4))]
+    fn parse_point(#[case] input: &str, #[case] expected: (i32, i32)) {
+        assert_eq!(parse_all(input, point::<i32, _>).unwrap(), expected);
+    }
+
+    #[rstest]
+    #[case("a")]
+    #[case("abc")]
+    #[case("snake_case_2")]
+    fn parse_identifier(#[case] input: &str) {
+        assert_eq!(parse_all(input, identifier).unwrap(), input);
+    }
+
+    #[test]
+    fn identifier_starts_with_a_letter() {
+        assert!(parse_all("2a", identifier).is_err());
+        assert!(parse_all("_a", identifier).is_err());
+    }
+
+    #[test]
+    fn works_with_the_default_error() {
+        let result: IResult<&str, (&str, &str, u32)> = route("A to B = 5 rest");
+        assert_eq!(result, Ok((" rest", ("A", "B", 5))));
+    }
+
+    #[rstest]
+    #[case(
+        "A to B = x",
+        "line 1, column 10: expected unsigned integer\nA to B = x\n         ^"
+    )]
+    #[case(
+        "A to B = 5 km",
+        "line 1, column 11: unexpected trailing input\nA to B = 5 km\n          ^"
+    )]
+    #[case("A from B = 5", "line 1, column 2: unexpected ` `\nA from B = 5\n ^")]
+    #[case("1 to B = 5", "line 1, column 1: expected identifier\n1 to B = 5\n^")]
+    fn error_messages(#[case] input: &str, #[case] expected: &str) {
+        let error = parse_all(input, route::<u32, _>).unwrap_err();
+        assert_eq!(error.to_string(), expected);
+    }
+
+    #[test]
+    fn errors_point_at_the_line() {
+        let input = "A to B = 1\nB to C = 2\nC to D = ?\nD to E = 3";
+
+        let error = parse_lines(input, route::<u32, _>).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 3, column 10: expected unsigned integer\nC to D = ?\n         ^"
+        );
+    }
+
+    #[test]
+    fn errors_point_at_the_line_of_a_multiline_input() {
+        let input = "1,2\n3;4";
+
+        let error = parse_all(input, |input| {
+            separated_pair(point::<i32, _>, char('\n'), point::<i32, _>)(input)
+        })
+        .unwrap_err();
+        assert_eq!(error.to_string(), "line 2, column 2: expected ','\n3;4\n ^");
+    }
+
+    #[test]
+    fn parse_every_line() {
+        let input = "A to B = 1\nB to C = 2";
+        assert_eq!(
+            parse_lines(input, route::<u32, _>).unwrap(),
+            vec![("A", "B", 1), ("B", "C", 2)]
+        );
+    }
+}
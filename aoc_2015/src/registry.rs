@@ -0,0 +1,356 @@
+use std::{
+    any::Any,
+    error::Error,
+    fmt,
+    panic::{self, AssertUnwindSafe},
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use aoc_runner::{ArcStr, Runner};
+use derive_more::derive::Display;
+
+use crate::Factory;
+
+/// Builds the `aoc-runner` runner of a solution from its input.
+pub type RunnerFactory = fn(ArcStr) -> Result<Box<dyn Runner>, Box<dyn Error>>;
+
+/// A solution registered with `#[aoc(dayN, partM)]`, reachable at runtime.
+pub struct Solution {
+    pub day: u8,
+    pub part: u8,
+    pub variant: Option<&'static str>,
+    pub factory: RunnerFactory,
+    /// Whether the solution only returns a hard-coded answer.
+    pub placeholder: bool,
+    /// The source of the `dayN` module, to tell when a cached result is stale.
+    pub source: &'static str,
+}
+
+/// The answer of a solution, along with the time spent on each step.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    pub answer: String,
+    pub generator: Duration,
+    pub runner: Duration,
+}
+
+impl Outcome {
+    pub fn total(&self) -> Duration {
+        self.generator + self.runner
+    }
+}
+
+/// Why a solution failed to produce an answer.
+#[derive(Debug, Display, Clone, PartialEq, Eq)]
+pub enum RunError {
+    #[display("FAILED while generating: {_0}")]
+    Generator(String),
+    #[display("FAILED while running: {_0}")]
+    Runner(String),
+}
+
+impl Solution {
+    /// The day name, as used by the inputs (`day6`).
+    pub fn day_name(&self) -> String {
+        format!("day{}", self.day)
+    }
+
+    /// The shared modules the solution runs, beside its `dayN` module.
+    pub fn dependencies(&self) -> &'static [Module] {
+        DEPENDENCIES
+            .iter()
+            .find(|(day, _)| *day == self.day)
+            .map_or(&[], |(_, modules)| modules)
+    }
+
+    /// The files the solution reads while running.
+    pub fn data_files(&self) -> Vec<PathBuf> {
+        DATA_FILES
+            .iter()
+            .filter(|(day, _)| *day == self.day)
+            .flat_map(|(_, files)| files.iter())
+            .map(|file| Path::new(env!("CARGO_MANIFEST_DIR")).join(file))
+            .collect()
+    }
+
+    /// Every source the result of the solution depends on: its `dayN`
+    /// module, then its dependencies.
+    pub fn sources(&self) -> impl Iterator<Item = &'static str> + '_ {
+        std::iter::once(self.source).chain(self.dependencies().iter().map(|m| m.source))
+    }
+
+    /// Runs the solution against `input`, catching both errors and panics.
+    pub fn run(&self, input: &str) -> Result<Outcome, RunError> {
+        let start_time = Instant::now();
+
+        let runner = match panic::catch_unwind(|| (self.factory)(ArcStr::from(input))) {
+            Ok(Ok(runner)) => runner,
+            Ok(Err(e)) => return Err(RunError::Generator(format!("{:?}", e))),
+            Err(payload) => return Err(RunError::Generator(panic_message(payload))),
+        };
+        let inter_time = Instant::now();
+
+        let answer = match panic::catch_unwind(AssertUnwindSafe(|| runner.try_run())) {
+            Ok(Ok(answer)) => answer.to_string(),
+            Ok(Err(e)) => return Err(RunError::Runner(format!("{:?}", e))),
+            Err(payload) => return Err(RunError::Runner(panic_message(payload))),
+        };
+        let final_time = Instant::now();
+
+        Ok(Outcome {
+            answer,
+            generator: inter_time - start_time,
+            runner: final_time - inter_time,
+        })
+    }
+}
+
+impl fmt::Display for Solution {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Day {} - Part {}", self.day, self.part)?;
+        if let Some(variant) = self.variant {
+            write!(f, " - {}", variant)?;
+        }
+        Ok(())
+    }
+}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "panicked".to_string()
+    }
+}
+
+/// Returns the solutions of `day`.
+pub fn solutions_of(day: u8) -> impl Iterator<Item = &'static Solution> {
+    SOLUTIONS.iter().filter(move |s| s.day == day)
+}
+
+/// A shared module the solutions of a day run.
+pub struct Module {
+    pub name: &'static str,
+    pub source: &'static str,
+}
+
+macro_rules! modules {
+    ($($name:literal),* $(,)?) => {
+        &[$(Module {
+            name: $name,
+            source: include_str!(concat!($name, ".rs")),
+        }),*]
+    };
+}
+
+/// The shared modules each day runs, directly or through another module.
+///
+/// A cached result is only as fresh as every source it was computed from, so
+/// these sources go into its key along with the `dayN` module. The
+/// `dependencies_match_crate_uses` test keeps the lists complete.
+static DEPENDENCIES: &[(u8, &[Module])] = &[
+    (1, modules!("iter", "rle")),
+    (3, modules!("geometry", "set")),
+    (4, modules!("miner")),
+    (
+        5,
+        modules!("error", "input", "iter", "parse", "rle", "rules"),
+    ),
+    (6, modules!("bitgrid", "error", "grid", "parse")),
+    (7, modules!("error", "interner", "memo", "parse")),
+    (8, modules!("codec")),
+    (
+        9,
+        modules!("error", "graph", "interner", "parse", "search", "trace"),
+    ),
+    (10, modules!("iter", "rle")),
+];
+
+/// The files each day reads while running, relative to the crate, such as
+/// the rule files of day 5. They can change without a rebuild, so the cache
+/// reads them again for each key.
+static DATA_FILES: &[(u8, &[&str])] = &[(
+    5,
+    &["rules/2015/day5-part1.rules", "rules/2015/day5-part2.rules"],
+)];
+
+macro_rules! solution {
+    ($day:literal, $part:literal, $factory:ident::$runner:ident $(, $placeholder:ident)?) => {
+        solution!(@build $day, $part, None, $factory::$runner $(, $placeholder)?)
+    };
+    ($day:literal, $part:literal, $variant:literal, $factory:ident::$runner:ident $(, $placeholder:ident)?) => {
+        solution!(@build $day, $part, Some($variant), $factory::$runner $(, $placeholder)?)
+    };
+    (@build $day:literal, $part:literal, $variant:expr, $factory:ident::$runner:ident) => {
+        Solution {
+            day: $day,
+            part: $part,
+            variant: $variant,
+            factory: <Factory as crate::$factory>::$runner,
+            placeholder: false,
+            source: include_str!(concat!("day", $day, ".rs")),
+        }
+    };
+    (@build $day:literal, $part:literal, $variant:expr, $factory:ident::$runner:ident, placeholder) => {
+        Solution {
+            placeholder: true,
+            ..solution!(@build $day, $part, $variant, $factory::$runner)
+        }
+    };
+}
+
+/// Every solution of the year, in day and part order.
+///
+/// Keep it in sync with the `#[aoc]` attributes: `aoc-runner` only exposes
+/// them through the `Factory` traits it generates. Solutions that only return
+/// a hard-coded answer are flagged as `placeholder`.
+pub static SOLUTIONS: &[Solution] = &[
+    solution!(1, 1, Day1Part1::day1_part1),
+    solution!(1, 2, Day1Part2::day1_part2),
+    solution!(2, 1, Day2Part1::day2_part1),
+    solution!(2, 2, Day2Part2::day2_part2),
+    solution!(3, 1, Day3Part1::day3_part1),
+    solution!(3, 2, Day3Part2::day3_part2),
+    solution!(4, 1, Day4Part1::day4_part1),
+    solution!(4, 2, Day4Part2::day4_part2),
+    solution!(5, 1, "Clearer", Day5Part1CLEARER::day5_part1_clearer),
+    solution!(5, 2, Day5Part2::day5_part2),
+    solution!(6, 1, Day6Part1::day6_part1),
+    solution!(6, 1, "Bits", Day6Part1BITS::day6_part1_bits),
+    solution!(6, 1, "Blocks", Day6Part1BLOCKS::day6_part1_blocks),
+    solution!(6, 2, Day6Part2::day6_part2),
+    solution!(6, 2, "Blocks", Day6Part2BLOCKS::day6_part2_blocks),
+    solution!(7, 1, Day7Part1::day7_part1),
+    solution!(7, 2, Day7Part2::day7_part2, placeholder),
+    solution!(8, 1, Day8Part1::day8_part1),
+    solution!(8, 2, Day8Part2::day8_part2),
+    solution!(9, 1, Day9Part1::day9_part1),
+    solution!(
+        9,
+        1,
+        "BranchAndBound",
+        Day9Part1BRANCHANDBOUND::day9_part1_branchandbound
+    ),
+    solution!(9, 2, Day9Part2::day9_part2),
+    solution!(
+        9,
+        2,
+        "BranchAndBound",
+        Day9Part2BRANCHANDBOUND::day9_part2_branchandbound
+    ),
+    solution!(10, 1, Day10Part1::day10_part1),
+    solution!(10, 2, Day10Part2::day10_part2),
+];
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeSet, fs};
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn read_module(name: &str) -> Option<String> {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join(format!("src/{}.rs", name));
+        fs::read_to_string(path).ok()
+    }
+
+    /// The names following `crate::` in `source`, outside of its comments
+    /// and tests: a path, or each path of a `crate::{...}` list.
+    fn crate_uses(source: &str) -> Vec<&str> {
+        let code = source.split("#[cfg(test)]").next().unwrap_or_default();
+
+        let mut names = Vec::new();
+        for (i, _) in code.match_indices("crate::") {
+            let line_start = code[..i].rfind('\n').map_or(0, |n| n + 1);
+            if code[line_start..i].trim_start().starts_with("//") {
+                continue;
+            }
+            let rest = &code[i + "crate::".len()..];
+            let Some(list) = rest.strip_prefix('{') else {
+                names.push(leading_identifier(rest));
+                continue;
+            };
+            let mut depth = 0;
+            let mut item_start = true;
+            for (j, c) in list.char_indices() {
+                match c {
+                    '}' if depth == 0 => break,
+                    '{' => depth += 1,
+                    '}' => depth -= 1,
+                    ',' if depth == 0 => item_start = true,
+                    c if c.is_whitespace() => {}
+                    _ => {
+                        if item_start && depth == 0 {
+                            names.push(leading_identifier(&list[j..]));
+                        }
+                        item_start = false;
+                    }
+                }
+            }
+        }
+        names
+    }
+
+    fn leading_identifier(s: &str) -> &str {
+        let end = s
+            .find(|c: char| !c.is_alphanumeric() && c != '_')
+            .unwrap_or(s.len());
+        &s[..end]
+    }
+
+    /// The modules `source` uses, and those they use in turn.
+    fn closure(source: &str) -> BTreeSet<String> {
+        let mut modules = BTreeSet::new();
+        let mut pending = vec![source.to_string()];
+        while let Some(source) = pending.pop() {
+            for name in crate_uses(&source) {
+                if modules.contains(name) {
+                    continue;
+                }
+                if let Some(module) = read_module(name) {
+                    modules.insert(name.to_string());
+                    pending.push(module);
+                }
+            }
+        }
+        modules
+    }
+
+    #[test]
+    fn dependencies_match_crate_uses() {
+        for solution in SOLUTIONS {
+            let registered: BTreeSet<_> = solution
+                .dependencies()
+                .iter()
+                .map(|m| m.name.to_string())
+                .collect();
+            assert_eq!(registered, closure(solution.source), "{}", solution);
+        }
+    }
+
+    #[test]
+    fn uses_in_lists_and_paths() {
+        let source = "use crate::{a::{B, C}, d, e::f};\n// crate::g\nfn h() { crate::i!(); }\n";
+        assert_eq!(crate_uses(source), ["a", "d", "e", "i"]);
+    }
+
+    #[test]
+    fn data_files_exist() {
+        for solution in SOLUTIONS {
+            for path in solution.data_files() {
+                assert!(path.is_file(), "{}: {}", solution, path.display());
+            }
+        }
+    }
+
+    #[test]
+    fn sources_start_with_the_day() {
+        let day4 = solutions_of(4).next().unwrap();
+        let sources: Vec<_> = day4.sources().collect();
+        assert_eq!(sources, [include_str!("day4.rs"), include_str!("miner.rs")]);
+    }
+}
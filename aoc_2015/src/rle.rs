@@ -0,0 +1,147 @@
+//! Run-length encoding: runs of equal items as `(value, count)` pairs.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use aoc_2015::rle::{decode, encode, encode_digits};
+//!
+//! let runs = encode(b"aaabcc");
+//! assert_eq!(runs, [(b'a', 3), (b'b', 1), (b'c', 2)]);
+//! assert_eq!(decode(&runs), b"aaabcc");
+//!
+//! // Look and say: three 1s, then two 2s, then one 1.
+//! assert_eq!(encode_digits(&[1, 1, 1, 2, 2, 1]), [3, 1, 2, 2, 1, 1]);
+//! ```
+
+use std::iter::Fuse;
+
+use crate::iter::IteratorExt;
+
+/// The iterator of [`IteratorExt::runs`].
+pub struct Runs<I: Iterator> {
+    iter: Fuse<I>,
+    /// The first item of the next run, read while counting the previous one.
+    pending: Option<I::Item>,
+}
+
+impl<I: Iterator> Runs<I> {
+    pub(crate) fn new(iter: I) -> Self {
+        Self {
+            iter: iter.fuse(),
+            pending: None,
+        }
+    }
+}
+
+impl<I> Iterator for Runs<I>
+where
+    I: Iterator,
+    I::Item: PartialEq,
+{
+    type Item = (I::Item, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let value = self.pending.take().or_else(|| self.iter.next())?;
+        let mut count = 1;
+        for item in self.iter.by_ref() {
+            if item == value {
+                count += 1;
+            } else {
+                self.pending = Some(item);
+                break;
+            }
+        }
+        Some((value, count))
+    }
+}
+
+/// The runs of `items`.
+pub fn encode<T: PartialEq + Copy>(items: &[T]) -> Vec<(T, usize)> {
+    items.iter().copied().runs().collect()
+}
+
+/// The items of `runs`, each repeated as many times as its run says.
+pub fn decode<T: Copy>(runs: &[(T, usize)]) -> Vec<T> {
+    runs.iter()
+        .flat_map(|&(value, count)| std::iter::repeat_n(value, count))
+        .collect()
+}
+
+/// Describes `digits` the look-and-say way: for each run, the digits of its
+/// length, then its digit.
+pub fn encode_digits(digits: &[u8]) -> Vec<u8> {
+    let mut encoded = Vec::with_capacity(digits.len() * 2);
+    for (digit, count) in digits.iter().copied().runs() {
+        if count < 10 {
+            encoded.push(count as u8);
+        } else {
+            encoded.extend(count.to_string().bytes().map(|b| b - b'0'));
+        }
+        encoded.push(digit);
+    }
+    encoded
+}
+
+/// Reverses [`encode_digits`], reading `encoded` as pairs of a run length and
+/// a digit. Runs of 10 digits or more are not told apart from shorter ones,
+/// so this only works when every run was shorter: returns `None` for an odd
+/// number of digits.
+pub fn decode_digits(encoded: &[u8]) -> Option<Vec<u8>> {
+    if !encoded.len().is_multiple_of(2) {
+        return None;
+    }
+    let runs = encoded
+        .chunks_exact(2)
+        .map(|pair| (pair[1], pair[0] as usize))
+        .collect::<Vec<_>>();
+    Some(decode(&runs))
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::*;
+
+    #[test]
+    fn runs_of_any_items() {
+        let words = ["a", "a", "b", "a"];
+        assert_eq!(
+            words.into_iter().runs().collect::<Vec<_>>(),
+            [("a", 2), ("b", 1), ("a", 1)]
+        );
+        assert_eq!(std::iter::empty::<u8>().runs().next(), None);
+    }
+
+    #[test]
+    fn runs_are_fused() {
+        let mut runs = [1, 1].into_iter().runs();
+        assert_eq!(runs.next(), Some((1, 2)));
+        assert_eq!(runs.next(), None);
+        assert_eq!(runs.next(), None);
+    }
+
+    #[rstest]
+    #[case(b"", &[])]
+    #[case(b"x", &[(b'x', 1)])]
+    #[case(b"xxxyx", &[(b'x', 3), (b'y', 1), (b'x', 1)])]
+    fn bytes_roundtrip(#[case] bytes: &[u8], #[case] runs: &[(u8, usize)]) {
+        assert_eq!(encode(bytes), runs);
+        assert_eq!(decode(runs), bytes);
+    }
+
+    #[test]
+    fn long_runs_of_digits() {
+        let digits = [7; 12];
+        assert_eq!(encode_digits(&digits), [1, 2, 7]);
+    }
+
+    #[rstest]
+    #[case(&[], Some(vec![]))]
+    #[case(&[3, 1, 2, 2, 1, 1], Some(vec![1, 1, 1, 2, 2, 1]))]
+    #[case(&[3, 1, 2], None)]
+    fn decode_look_and_say(#[case] encoded: &[u8], #[case] expected: Option<Vec<u8>>) {
+        assert_eq!(decode_digits(encoded), expected);
+    }
+}
@@ -0,0 +1,342 @@
+//! A small language of rules classifying strings, read from rule files so
+//! that a policy can change without recompiling.
+//!
+//! A rule is a call: a primitive, or `all`, `any` or `not` of other rules.
+//! Whitespace and `#` comments may go anywhere between tokens.
+//!
+//! | Rule                        | Matches strings with                                |
+//! |-----------------------------|-----------------------------------------------------|
+//! | `at_least(n, "set")`        | at least `n` characters from the set                |
+//! | `run(k)`                    | a character `k` times in a row                      |
+//! | `forbid("ab", "cd", ...)`   | none of the substrings                              |
+//! | `pair_twice(gap)`           | two characters twice, with at least `gap` between   |
+//! | `pattern("x?x")`            | a window where each letter stands for one character |
+//! | `all(...)`, `any(...)`      | every rule, or one of them                          |
+//! | `not(rule)`                 | no match for the rule                               |
+//!
+//! In a pattern, `?` matches any character, and any other character is a
+//! variable matching the same character wherever it appears.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use aoc_2015::rules::Rule;
+//!
+//! let rule: Rule = r#"
+//!     ## Three vowels, and no `xy` or double `y`.
+//!     all(at_least(3, "aeiou"), not(any(forbid("xy"), run(2))))
+//! "#
+//! .parse()
+//! .unwrap();
+//! assert!(rule.matches("aeixy"));
+//! assert!(!rule.matches("aeiou"));
+//!
+//! let error = "all(run(2), pair(0))".parse::<Rule>().unwrap_err();
+//! assert_eq!(
+//!     error.to_string(),
+//!     "line 1, column 13: expected rule\nall(run(2), pair(0))\n            ^"
+//! );
+//! ```
+
+use std::{
+    collections::HashMap,
+    fmt,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use nom::{
+    branch::alt,
+    bytes::complete::take_till,
+    character::complete::{char, multispace1, not_line_ending},
+    combinator::{cut, map},
+    error::{context, VerboseError, VerboseErrorKind},
+    multi::{many0_count, separated_list0},
+    sequence::{delimited, pair, preceded, separated_pair, terminated},
+};
+
+use crate::{
+    error::{Error, Result},
+    input,
+    iter::IteratorExt,
+    parse::{identifier, parse_all, unsigned, ParseResult},
+};
+
+/// The directory holding the rule files of the year.
+pub fn rules_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("rules/2015")
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Rule {
+    AtLeast { count: usize, set: String },
+    Run(usize),
+    Forbid(Vec<String>),
+    PairTwice { gap: usize },
+    Pattern(String),
+    All(Vec<Rule>),
+    Any(Vec<Rule>),
+    Not(Box<Rule>),
+}
+
+impl Rule {
+    /// Reads a rule file.
+    pub fn load(path: &Path) -> Result<Self> {
+        input::read(path)?.parse()
+    }
+
+    pub fn matches(&self, s: &str) -> bool {
+        match self {
+            Rule::AtLeast { count, set } => {
+                s.chars().filter(|&c| set.contains(c)).count() >= *count
+            }
+            Rule::Run(length) => *length == 0 || s.chars().runs().any(|(_, run)| run >= *length),
+            Rule::Forbid(substrings) => !substrings.iter().any(|sub| s.contains(sub.as_str())),
+            Rule::PairTwice { gap } => has_pair_twice(s, *gap),
+            Rule::Pattern(pattern) => matches_pattern(s, pattern),
+            Rule::All(rules) => rules.iter().all(|rule| rule.matches(s)),
+            Rule::Any(rules) => rules.iter().any(|rule| rule.matches(s)),
+            Rule::Not(rule) => !rule.matches(s),
+        }
+    }
+}
+
+/// Whether two characters appear twice in `s`, with at least `gap`
+/// characters between the pairs.
+fn has_pair_twice(s: &str, gap: usize) -> bool {
+    // The first position of each pair is the one furthest from any later
+    // position.
+    let mut first = HashMap::new();
+    let chars: Vec<char> = s.chars().collect();
+    chars.windows(2).enumerate().any(|(i, pair)| {
+        let start = *first.entry(pair).or_insert(i);
+        i >= start + 2 + gap
+    })
+}
+
+/// Whether a window of `s` matches `pattern`.
+fn matches_pattern(s: &str, pattern: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let chars: Vec<char> = s.chars().collect();
+    if pattern.is_empty() {
+        return true;
+    }
+    chars.windows(pattern.len()).any(|window| {
+        let mut bound = HashMap::new();
+        pattern
+            .iter()
+            .zip(window)
+            .all(|(&variable, &c)| variable == '?' || *bound.entry(variable).or_insert(c) == c)
+    })
+}
+
+impl FromStr for Rule {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        parse_all(s, delimited(parse_blank, parse_rule, parse_blank))
+    }
+}
+
+/// Whitespace and comments.
+fn parse_blank(input: &str) -> ParseResult<'_, ()> {
+    map(
+        many0_count(alt((
+            map(multispace1, |_| ()),
+            map(pair(char('#'), not_line_ending), |_| ()),
+        ))),
+        |_| (),
+    )(input)
+}
+
+fn parse_rule(input: &str) -> ParseResult<'_, Rule> {
+    let (rest, name) =
+        identifier::<()>(input).map_err(|_| nom::Err::Error(expected_rule(input)))?;
+    let (rest, _) = preceded(parse_blank, context("`(`", char('(')))(rest)?;
+    let (rest, rule) = match name {
+        "all" => map(parse_list(parse_rule), Rule::All)(rest)?,
+        "any" => map(parse_list(parse_rule), Rule::Any)(rest)?,
+        "not" => map(parse_argument(parse_rule), |rule| Rule::Not(Box::new(rule)))(rest)?,
+        "at_least" => map(
+            separated_pair(
+                parse_argument(unsigned),
+                parse_comma,
+                parse_argument(parse_string),
+            ),
+            |(count, set)| Rule::AtLeast { count, set },
+        )(rest)?,
+        "run" => map(parse_argument(unsigned), Rule::Run)(rest)?,
+        "forbid" => map(parse_list(parse_string), Rule::Forbid)(rest)?,
+        "pair_twice" => map(parse_argument(unsigned), |gap| Rule::PairTwice { gap })(rest)?,
+        "pattern" => map(parse_argument(parse_string), Rule::Pattern)(rest)?,
+        _ => return Err(nom::Err::Failure(expected_rule(input))),
+    };
+    let (rest, _) = cut(preceded(parse_blank, context("`)`", char(')'))))(rest)?;
+    Ok((rest, rule))
+}
+
+/// The error of a rule name missing at `input`, or not one of the known ones.
+fn expected_rule(input: &str) -> VerboseError<&str> {
+    VerboseError {
+        errors: vec![(input, VerboseErrorKind::Context("rule"))],
+    }
+}
+
+/// Arguments separated by commas, up to the closing parenthesis.
+fn parse_list<'a, T>(
+    argument: impl FnMut(&'a str) -> ParseResult<'a, T>,
+) -> impl FnMut(&'a str) -> ParseResult<'a, Vec<T>> {
+    separated_list0(parse_comma, parse_argument(argument))
+}
+
+/// An argument, and the blanks before it.
+fn parse_argument<'a, T>(
+    argument: impl FnMut(&'a str) -> ParseResult<'a, T>,
+) -> impl FnMut(&'a str) -> ParseResult<'a, T> {
+    preceded(parse_blank, argument)
+}
+
+fn parse_comma(input: &str) -> ParseResult<'_, char> {
+    preceded(parse_blank, char(','))(input)
+}
+
+/// A string between double quotes, with no escapes.
+fn parse_string(input: &str) -> ParseResult<'_, String> {
+    map(
+        preceded(
+            context("string", char('"')),
+            cut(terminated(
+                take_till(|c| c == '"' || c == '\n'),
+                context("closing quote", char('"')),
+            )),
+        ),
+        str::to_string,
+    )(input)
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn list<T: fmt::Display>(f: &mut fmt::Formatter<'_>, items: &[T]) -> fmt::Result {
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    write!(f, ", ")?;
+                }
+                write!(f, "{}", item)?;
+            }
+            Ok(())
+        }
+        let quoted = |strings: &[String]| {
+            strings
+                .iter()
+                .map(|s| format!("\"{}\"", s))
+                .collect::<Vec<_>>()
+        };
+
+        match self {
+            Rule::AtLeast { count, set } => write!(f, "at_least({}, \"{}\")", count, set),
+            Rule::Run(length) => write!(f, "run({})", length),
+            Rule::Forbid(substrings) => {
+                write!(f, "forbid(")?;
+                list(f, &quoted(substrings))?;
+                write!(f, ")")
+            }
+            Rule::PairTwice { gap } => write!(f, "pair_twice({})", gap),
+            Rule::Pattern(pattern) => write!(f, "pattern(\"{}\")", pattern),
+            Rule::All(rules) => {
+                write!(f, "all(")?;
+                list(f, rules)?;
+                write!(f, ")")
+            }
+            Rule::Any(rules) => {
+                write!(f, "any(")?;
+                list(f, rules)?;
+                write!(f, ")")
+            }
+            Rule::Not(rule) => write!(f, "not({})", rule),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::*;
+
+    fn rule(source: &str) -> Rule {
+        source.parse().unwrap()
+    }
+
+    #[rstest]
+    #[case("run(2)", Rule::Run(2))]
+    #[case("  run ( 2 )  ", Rule::Run(2))]
+    #[case("forbid()", Rule::Forbid(vec![]))]
+    #[case(
+        "at_least(3,\"aeiou\")",
+        Rule::AtLeast { count: 3, set: "aeiou".to_string() }
+    )]
+    #[case(
+        "not(any(run(3), pattern(\"ab?\")))",
+        Rule::Not(Box::new(Rule::Any(vec![Rule::Run(3), Rule::Pattern("ab?".to_string())])))
+    )]
+    #[case(
+        "all( # the first\n  pair_twice(1),\n  # and the second\n  run(1)\n)",
+        Rule::All(vec![Rule::PairTwice { gap: 1 }, Rule::Run(1)])
+    )]
+    fn parse_rules(#[case] source: &str, #[case] expected: Rule) {
+        assert_eq!(rule(source), expected);
+    }
+
+    #[rstest]
+    #[case("at_least(2, \"xy\")")]
+    #[case("forbid(\"ab\", \"cd\")")]
+    #[case("all(not(run(2)), any(pair_twice(0), pattern(\"x?x\")), all())")]
+    fn display_parses_back(#[case] source: &str) {
+        assert_eq!(rule(source).to_string(), source);
+    }
+
+    #[rstest]
+    #[case("", "line 1, column 1: expected rule")]
+    #[case("run", "line 1, column 4: expected `(`")]
+    #[case("run(2", "line 1, column 6: expected `)`")]
+    #[case("run(x)", "line 1, column 5: expected unsigned integer")]
+    #[case("forbid(\"ab)", "line 1, column 12: expected closing quote")]
+    #[case("all(\n  run(2),\n  twice(2)\n)", "line 3, column 3: expected rule")]
+    #[case("run(2) run(3)", "line 1, column 8: unexpected trailing input")]
+    fn parse_errors(#[case] source: &str, #[case] expected: &str) {
+        let error = source.parse::<Rule>().unwrap_err().to_string();
+        assert_eq!(error.lines().next().unwrap(), expected);
+    }
+
+    #[rstest]
+    #[case("run(3)", "abbbc", true)]
+    #[case("run(3)", "abbcbb", false)]
+    #[case("run(0)", "", true)]
+    #[case("at_least(2, \"xy\")", "xay", true)]
+    #[case("at_least(2, \"xy\")", "xa", false)]
+    #[case("forbid(\"ab\", \"cd\")", "acbd", true)]
+    #[case("forbid(\"ab\", \"cd\")", "xcdx", false)]
+    #[case("pair_twice(0)", "xyxy", true)]
+    #[case("pair_twice(0)", "aaa", false)]
+    #[case("pair_twice(0)", "aaaa", true)]
+    #[case("pair_twice(2)", "xyaxy", false)]
+    #[case("pair_twice(2)", "xyabxy", true)]
+    #[case("pattern(\"x?x\")", "abcb", true)]
+    #[case("pattern(\"x?x\")", "abcd", false)]
+    #[case("pattern(\"xyyx\")", "zabbaz", true)]
+    #[case("pattern(\"xyyx\")", "zabbcz", false)]
+    #[case("pattern(\"\")", "", true)]
+    #[case("all()", "", true)]
+    #[case("any()", "", false)]
+    #[case("not(run(2))", "abab", true)]
+    fn rules_match(#[case] source: &str, #[case] s: &str, #[case] expected: bool) {
+        assert_eq!(rule(source).matches(s), expected, "{} on {:?}", source, s);
+    }
+
+    #[test]
+    fn load_missing_file() {
+        let error = Rule::load(&rules_dir().join("missing.rules")).unwrap_err();
+        assert!(matches!(error, Error::Io { .. }), "{}", error);
+    }
+}
@@ -0,0 +1,503 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, VecDeque},
+    hash::Hash,
+    ops::Add,
+    time::{Duration, Instant},
+};
+
+/// A state of a puzzle, searched from a start state towards a goal.
+///
+/// # Examples
+///
+/// ```rust
+/// use aoc_2015::search::{astar, State};
+///
+/// /// Reach a number from 1 by doubling it or adding one to it.
+/// #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+/// struct Number(u32, u32);
+///
+/// impl State for Number {
+///     type Cost = u32;
+///
+///     fn successors(&self) -> Vec<(Self, u32)> {
+///         let Number(n, target) = *self;
+///         [n + 1, n * 2]
+///             .into_iter()
+///             .filter(|&next| next <= target)
+///             .map(|next| (Number(next, target), 1))
+///             .collect()
+///     }
+///
+///     fn is_goal(&self) -> bool {
+///         self.0 == self.1
+///     }
+/// }
+///
+/// let report = astar(Number(1, 100));
+/// let solution = report.solution.unwrap();
+///
+/// assert_eq!(solution.cost, 8);
+/// assert_eq!(
+///     solution.path.iter().map(|state| state.0).collect::<Vec<_>>(),
+///     [1, 2, 3, 6, 12, 24, 25, 50, 100]
+/// );
+/// ```
+pub trait State: Clone + Eq + Hash {
+    type Cost: Copy + Ord + Default + Add<Output = Self::Cost>;
+
+    /// The states one move away, with the cost of each move.
+    fn successors(&self) -> Vec<(Self, Self::Cost)>;
+
+    fn is_goal(&self) -> bool;
+
+    /// A lower bound of the cost left to reach a goal. The default of no cost
+    /// turns [`astar`] into [`uniform_cost`].
+    fn heuristic(&self) -> Self::Cost {
+        Self::Cost::default()
+    }
+}
+
+/// The states from the start to a goal inclusive, and their total cost.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Solution<S, C> {
+    pub path: Vec<S>,
+    pub cost: C,
+}
+
+/// How much work a search did.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub struct Stats {
+    /// The states whose successors were generated.
+    pub expanded: usize,
+    pub elapsed: Duration,
+}
+
+/// The outcome of a search: its solution, if it found one, and its stats.
+#[derive(Debug, Clone)]
+pub struct Report<S, C> {
+    pub solution: Option<Solution<S, C>>,
+    pub stats: Stats,
+}
+
+impl<S, C> Report<S, C> {
+    fn new(solution: Option<Solution<S, C>>, expanded: usize, started: Instant) -> Self {
+        Self {
+            solution,
+            stats: Stats {
+                expanded,
+                elapsed: started.elapsed(),
+            },
+        }
+    }
+}
+
+/// The states seen by [`bfs`] and [`best_first`], numbered so that their
+/// parents are indices rather than copies.
+struct Explored<S, C> {
+    states: Vec<S>,
+    index: HashMap<S, usize>,
+    cost: Vec<C>,
+    parent: Vec<Option<usize>>,
+}
+
+impl<S: State> Explored<S, S::Cost> {
+    fn new(start: S) -> Self {
+        Self {
+            index: HashMap::from([(start.clone(), 0)]),
+            states: vec![start],
+            cost: vec![S::Cost::default()],
+            parent: vec![None],
+        }
+    }
+
+    /// Records that `state` is reached at `cost` from `parent`, unless it
+    /// was already reached as cheaply. Returns its index if it was updated.
+    fn reach(&mut self, state: S, cost: S::Cost, parent: usize) -> Option<usize> {
+        match self.index.get(&state) {
+            Some(&i) if self.cost[i] <= cost => None,
+            Some(&i) => {
+                self.cost[i] = cost;
+                self.parent[i] = Some(parent);
+                Some(i)
+            }
+            None => {
+                let i = self.states.len();
+                self.index.insert(state.clone(), i);
+                self.states.push(state);
+                self.cost.push(cost);
+                self.parent.push(Some(parent));
+                Some(i)
+            }
+        }
+    }
+
+    fn solution(&self, goal: usize) -> Solution<S, S::Cost> {
+        let mut path = vec![self.states[goal].clone()];
+        let mut current = goal;
+        while let Some(parent) = self.parent[current] {
+            path.push(self.states[parent].clone());
+            current = parent;
+        }
+        path.reverse();
+        Solution {
+            path,
+            cost: self.cost[goal],
+        }
+    }
+}
+
+/// Breadth-first search: the goal reached in the fewest moves, whatever they
+/// cost.
+pub fn bfs<S: State>(start: S) -> Report<S, S::Cost> {
+    let started = Instant::now();
+    let mut explored = Explored::new(start);
+    let mut queue = VecDeque::from([0]);
+    let mut expanded = 0;
+
+    while let Some(current) = queue.pop_front() {
+        if explored.states[current].is_goal() {
+            return Report::new(Some(explored.solution(current)), expanded, started);
+        }
+        expanded += 1;
+        for (next, step) in explored.states[current].successors() {
+            if explored.index.contains_key(&next) {
+                continue;
+            }
+            let cost = explored.cost[current] + step;
+            if let Some(i) = explored.reach(next, cost, current) {
+                queue.push_back(i);
+            }
+        }
+    }
+    Report::new(None, expanded, started)
+}
+
+/// Uniform-cost search (Dijkstra): the cheapest goal, ignoring the heuristic.
+pub fn uniform_cost<S: State>(start: S) -> Report<S, S::Cost> {
+    best_first(start, |_| S::Cost::default())
+}
+
+/// A*: the cheapest goal, exploring first the states whose
+/// [`State::heuristic`] promises the cheapest total. The heuristic must never
+/// overestimate for the goal to be the cheapest.
+pub fn astar<S: State>(start: S) -> Report<S, S::Cost> {
+    best_first(start, S::heuristic)
+}
+
+fn best_first<S: State>(start: S, heuristic: impl Fn(&S) -> S::Cost) -> Report<S, S::Cost> {
+    let started = Instant::now();
+    let mut heap = BinaryHeap::from([Reverse((heuristic(&start), 0))]);
+    let mut explored = Explored::new(start);
+    let mut closed = vec![false];
+    let mut expanded = 0;
+
+    while let Some(Reverse((_, current))) = heap.pop() {
+        if closed[current] {
+            continue;
+        }
+        closed[current] = true;
+        if explored.states[current].is_goal() {
+            return Report::new(Some(explored.solution(current)), expanded, started);
+        }
+
+        expanded += 1;
+        for (next, step) in explored.states[current].successors() {
+            let cost = explored.cost[current] + step;
+            let estimate = cost + heuristic(&next);
+            if let Some(i) = explored.reach(next, cost, current) {
+                if i == closed.len() {
+                    closed.push(false);
+                }
+                closed[i] = false;
+                heap.push(Reverse((estimate, i)));
+            }
+        }
+    }
+    Report::new(None, expanded, started)
+}
+
+/// A state on the path of a depth-first search, with the successors left to
+/// try from it.
+struct Frame<S: State> {
+    state: S,
+    cost: S::Cost,
+    successors: std::vec::IntoIter<(S, S::Cost)>,
+}
+
+impl<S: State> Frame<S> {
+    fn new(state: S, cost: S::Cost) -> Self {
+        let successors = state.successors().into_iter();
+        Self {
+            state,
+            cost,
+            successors,
+        }
+    }
+}
+
+fn path_to<S: State>(frames: &[Frame<S>], last: S) -> Vec<S> {
+    let mut path = frames
+        .iter()
+        .map(|frame| frame.state.clone())
+        .collect::<Vec<_>>();
+    path.push(last);
+    path
+}
+
+/// Iterative deepening A*: depth-first searches bounded by the estimated total
+/// cost, raising the bound to the smallest estimate over it until a goal is
+/// found. It only keeps the current path in memory, at the price of exploring
+/// states again.
+pub fn ida_star<S: State>(start: S) -> Report<S, S::Cost> {
+    let started = Instant::now();
+    if start.is_goal() {
+        let solution = Solution {
+            path: vec![start],
+            cost: S::Cost::default(),
+        };
+        return Report::new(Some(solution), 0, started);
+    }
+
+    let mut expanded = 0;
+    let mut bound = start.heuristic();
+    loop {
+        let mut frames = vec![Frame::new(start.clone(), S::Cost::default())];
+        expanded += 1;
+        let mut next_bound: Option<S::Cost> = None;
+
+        while let Some(frame) = frames.last_mut() {
+            let Some((next, step)) = frame.successors.next() else {
+                frames.pop();
+                continue;
+            };
+            let cost = frame.cost + step;
+            let estimate = cost + next.heuristic();
+            if estimate > bound {
+                next_bound = Some(next_bound.map_or(estimate, |b| b.min(estimate)));
+                continue;
+            }
+            if frames.iter().any(|frame| frame.state == next) {
+                continue;
+            }
+            if next.is_goal() {
+                let solution = Solution {
+                    path: path_to(&frames, next),
+                    cost,
+                };
+                return Report::new(Some(solution), expanded, started);
+            }
+
+            expanded += 1;
+            frames.push(Frame::new(next, cost));
+        }
+
+        match next_bound {
+            Some(raised) => bound = raised,
+            None => return Report::new(None, expanded, started),
+        }
+    }
+}
+
+/// Depth-first branch and bound: explores every path, but drops the ones
+/// whose cost plus [`State::heuristic`] cannot beat the best goal found so
+/// far, and those `prune` rejects given the state and the cost to reach it.
+///
+/// It finds the cheapest goal with little memory when goals are all at about
+/// the same depth, like complete tours.
+pub fn branch_and_bound<S, P>(start: S, mut prune: P) -> Report<S, S::Cost>
+where
+    S: State,
+    P: FnMut(&S, S::Cost) -> bool,
+{
+    let started = Instant::now();
+    if start.is_goal() {
+        let solution = Solution {
+            path: vec![start],
+            cost: S::Cost::default(),
+        };
+        return Report::new(Some(solution), 0, started);
+    }
+
+    let mut best: Option<Solution<S, S::Cost>> = None;
+    let mut frames = vec![Frame::new(start, S::Cost::default())];
+    let mut expanded = 1;
+
+    while let Some(frame) = frames.last_mut() {
+        let Some((next, step)) = frame.successors.next() else {
+            frames.pop();
+            continue;
+        };
+        let cost = frame.cost + step;
+        if best
+            .as_ref()
+            .is_some_and(|best| cost + next.heuristic() >= best.cost)
+        {
+            continue;
+        }
+        if prune(&next, cost) || frames.iter().any(|frame| frame.state == next) {
+            continue;
+        }
+        if next.is_goal() {
+            best = Some(Solution {
+                path: path_to(&frames, next),
+                cost,
+            });
+            continue;
+        }
+
+        expanded += 1;
+        frames.push(Frame::new(next, cost));
+    }
+    Report::new(best, expanded, started)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use crate::geometry::{Direction, Vec2};
+
+    use super::*;
+
+    /// A 5x5 grid with a wall along x == 2, except at y == 4, walked from the
+    /// top-left corner to the top-right one.
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+    struct Maze(Vec2);
+
+    const GOAL: Vec2 = Vec2 { x: 4, y: 0 };
+
+    impl State for Maze {
+        type Cost = i64;
+
+        fn successors(&self) -> Vec<(Self, i64)> {
+            let wall = |p: Vec2| p.x == 2 && p.y != 4;
+            Direction::ALL
+                .into_iter()
+                .map(|d| self.0 + d.offset())
+                .filter(|p| (0..5).contains(&p.x) && (0..5).contains(&p.y) && !wall(*p))
+                .map(|p| (Maze(p), 1))
+                .collect()
+        }
+
+        fn is_goal(&self) -> bool {
+            self.0 == GOAL
+        }
+
+        fn heuristic(&self) -> i64 {
+            self.0.manhattan(GOAL)
+        }
+    }
+
+    /// Weighted moves where the fewest moves are not the cheapest: the direct
+    /// move from `a` to `d` costs more than going through `b` and `c`.
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+    struct Node(char);
+
+    impl State for Node {
+        type Cost = u32;
+
+        fn successors(&self) -> Vec<(Self, u32)> {
+            let moves: &[(char, u32)] = match self.0 {
+                'a' => &[('b', 1), ('d', 10)],
+                'b' => &[('a', 1), ('c', 1)],
+                'c' => &[('d', 1)],
+                _ => &[],
+            };
+            moves.iter().map(|&(to, cost)| (Node(to), cost)).collect()
+        }
+
+        fn is_goal(&self) -> bool {
+            self.0 == 'd'
+        }
+    }
+
+    type Search<S> = fn(S) -> Report<S, <S as State>::Cost>;
+
+    fn bnb<S: State>(start: S) -> Report<S, S::Cost> {
+        branch_and_bound(start, |_, _| false)
+    }
+
+    #[rstest]
+    #[case::bfs(bfs as Search<Maze>)]
+    #[case::uniform_cost(uniform_cost as Search<Maze>)]
+    #[case::astar(astar as Search<Maze>)]
+    #[case::ida_star(ida_star as Search<Maze>)]
+    #[case::branch_and_bound(bnb as Search<Maze>)]
+    fn shortest_path_around_a_wall(#[case] search: Search<Maze>) {
+        let solution = search(Maze(Vec2::ORIGIN)).solution.unwrap();
+
+        assert_eq!(solution.cost, 12);
+        assert_eq!(solution.path.len(), 13);
+        assert_eq!(solution.path.first(), Some(&Maze(Vec2::ORIGIN)));
+        assert_eq!(solution.path.last(), Some(&Maze(GOAL)));
+        for pair in solution.path.windows(2) {
+            assert_eq!(pair[0].0.manhattan(pair[1].0), 1);
+        }
+    }
+
+    #[rstest]
+    #[case::bfs(bfs as Search<Node>, "ad", 10)]
+    #[case::uniform_cost(uniform_cost as Search<Node>, "abcd", 3)]
+    #[case::astar(astar as Search<Node>, "abcd", 3)]
+    #[case::ida_star(ida_star as Search<Node>, "abcd", 3)]
+    #[case::branch_and_bound(bnb as Search<Node>, "abcd", 3)]
+    fn weighted_moves(#[case] search: Search<Node>, #[case] path: &str, #[case] cost: u32) {
+        let solution = search(Node('a')).solution.unwrap();
+
+        assert_eq!(solution.path.iter().map(|n| n.0).collect::<String>(), path);
+        assert_eq!(solution.cost, cost);
+    }
+
+    #[rstest]
+    #[case::bfs(bfs as Search<Node>)]
+    #[case::uniform_cost(uniform_cost as Search<Node>)]
+    #[case::astar(astar as Search<Node>)]
+    #[case::ida_star(ida_star as Search<Node>)]
+    #[case::branch_and_bound(bnb as Search<Node>)]
+    fn start_at_the_goal(#[case] search: Search<Node>) {
+        let report = search(Node('d'));
+
+        assert_eq!(
+            report.solution,
+            Some(Solution {
+                path: vec![Node('d')],
+                cost: 0
+            })
+        );
+    }
+
+    #[rstest]
+    #[case::bfs(bfs as Search<Node>)]
+    #[case::uniform_cost(uniform_cost as Search<Node>)]
+    #[case::astar(astar as Search<Node>)]
+    #[case::ida_star(ida_star as Search<Node>)]
+    #[case::branch_and_bound(bnb as Search<Node>)]
+    fn unreachable_goal(#[case] search: Search<Node>) {
+        // 'e' has no moves, and 'd' is not reachable from it.
+        assert_eq!(search(Node('e')).solution, None);
+    }
+
+    #[test]
+    fn heuristic_expands_fewer_states() {
+        // Straight up from the bottom-right corner: A* walks right to the goal,
+        // while uniform-cost search also looks behind the wall.
+        let start = Maze(Vec2::new(4, 4));
+        let uniform = uniform_cost(start.clone()).stats.expanded;
+        let informed = astar(start).stats.expanded;
+
+        assert_eq!(informed, 4);
+        assert!(informed < uniform, "{} >= {}", informed, uniform);
+    }
+
+    #[test]
+    fn pruning_hook() {
+        // Forbidding 'b' leaves only the expensive direct move.
+        let report = branch_and_bound(Node('a'), |node, _| node.0 == 'b');
+        let solution = report.solution.unwrap();
+
+        assert_eq!(solution.cost, 10);
+        assert_eq!(report.stats.expanded, 1);
+    }
+}
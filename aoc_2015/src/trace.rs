@@ -0,0 +1,89 @@
+use std::cell::RefCell;
+
+thread_local! {
+    static SINK: RefCell<Option<Vec<String>>> = const { RefCell::new(None) };
+}
+
+/// Emits a line of debug output from a solver.
+///
+/// The line goes to stderr, unless the solver runs inside [`capture`], in
+/// which case it is collected instead (e.g. to show it in the dashboard).
+#[macro_export]
+macro_rules! trace {
+    ($($arg:tt)*) => {
+        $crate::trace::emit(format!($($arg)*))
+    };
+}
+
+#[doc(hidden)]
+pub fn emit(line: String) {
+    SINK.with(|sink| match sink.borrow_mut().as_mut() {
+        Some(lines) => lines.push(line),
+        None => eprintln!("{}", line),
+    });
+}
+
+/// Restores the previous sink, even if the captured function panics.
+struct Guard(Option<Vec<String>>);
+
+impl Drop for Guard {
+    fn drop(&mut self) {
+        let previous = self.0.take();
+        SINK.with(|sink| *sink.borrow_mut() = previous);
+    }
+}
+
+/// Runs `f`, collecting every line it traces on this thread.
+pub fn capture<R>(f: impl FnOnce() -> R) -> (R, Vec<String>) {
+    let previous = SINK.with(|sink| sink.borrow_mut().replace(Vec::new()));
+    let guard = Guard(previous);
+
+    let result = f();
+    let lines = SINK
+        .with(|sink| sink.borrow_mut().take())
+        .unwrap_or_default();
+
+    drop(guard);
+    (result, lines)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn capture_lines() {
+        let (result, lines) = capture(|| {
+            crate::trace!("first {}", 1);
+            crate::trace!("second");
+            42
+        });
+
+        assert_eq!(result, 42);
+        assert_eq!(lines, vec!["first 1", "second"]);
+    }
+
+    #[test]
+    fn nested_capture() {
+        let (inner, outer) = capture(|| {
+            crate::trace!("outer");
+            let (_, inner) = capture(|| crate::trace!("inner"));
+            crate::trace!("outer again");
+            inner
+        });
+
+        assert_eq!(inner, vec!["inner"]);
+        assert_eq!(outer, vec!["outer", "outer again"]);
+    }
+
+    #[test]
+    fn capture_restores_after_panic() {
+        let result = std::panic::catch_unwind(|| capture(|| panic!("boom")));
+        assert!(result.is_err());
+
+        let (_, lines) = capture(|| crate::trace!("after"));
+        assert_eq!(lines, vec!["after"]);
+    }
+}
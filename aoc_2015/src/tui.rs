@@ -0,0 +1,732 @@
+use std::{
+    io::{self, Read, Write},
+    process::{Command, Stdio},
+};
+
+use crate::{
+    input::{self, NamedInput},
+    registry::{Outcome, RunError, Solution, SOLUTIONS},
+    trace, YEAR,
+};
+
+/// A grid of characters, drawn at once by a [`Backend`].
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Buffer {
+    width: usize,
+    height: usize,
+    cells: Vec<char>,
+}
+
+impl Buffer {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![' '; width * height],
+        }
+    }
+
+    /// Writes `text` at `(x, y)`, clipped to the buffer.
+    pub fn put(&mut self, x: usize, y: usize, text: &str) {
+        if y >= self.height {
+            return;
+        }
+        for (i, ch) in text.chars().enumerate() {
+            if x + i >= self.width {
+                break;
+            }
+            self.cells[x + i + y * self.width] = ch;
+        }
+    }
+
+    /// The rows of the buffer, without trailing spaces.
+    pub fn lines(&self) -> Vec<String> {
+        self.cells
+            .chunks(self.width.max(1))
+            .take(self.height)
+            .map(|row| row.iter().collect::<String>().trim_end().to_string())
+            .collect()
+    }
+}
+
+/// Where the dashboard is drawn.
+pub trait Backend {
+    /// The size of the drawing area, as `(width, height)`.
+    fn size(&self) -> (usize, usize);
+
+    fn draw(&mut self, buffer: &Buffer) -> io::Result<()>;
+}
+
+/// An in-memory backend keeping the last drawn buffer, for tests.
+#[derive(Debug)]
+pub struct TestBackend {
+    width: usize,
+    height: usize,
+    pub buffer: Buffer,
+}
+
+impl TestBackend {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            buffer: Buffer::new(width, height),
+        }
+    }
+}
+
+impl Backend for TestBackend {
+    fn size(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+
+    fn draw(&mut self, buffer: &Buffer) -> io::Result<()> {
+        self.buffer = buffer.clone();
+        Ok(())
+    }
+}
+
+/// A backend drawing to a real terminal with ANSI escape codes.
+pub struct TerminalBackend<W: Write> {
+    out: W,
+    width: usize,
+    height: usize,
+}
+
+impl<W: Write> TerminalBackend<W> {
+    pub fn new(out: W) -> Self {
+        let (width, height) = terminal_size().unwrap_or((80, 24));
+        Self { out, width, height }
+    }
+}
+
+impl<W: Write> Backend for TerminalBackend<W> {
+    fn size(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+
+    fn draw(&mut self, buffer: &Buffer) -> io::Result<()> {
+        write!(self.out, "\x1b[H\x1b[2J")?;
+        write!(self.out, "{}", buffer.lines().join("\r\n"))?;
+        self.out.flush()
+    }
+}
+
+fn terminal_size() -> Option<(usize, usize)> {
+    let output = Command::new("stty")
+        .arg("size")
+        .stdin(Stdio::inherit())
+        .output()
+        .ok()?;
+    let size = String::from_utf8(output.stdout).ok()?;
+    let (rows, cols) = size.trim().split_once(' ')?;
+    Some((cols.parse().ok()?, rows.parse().ok()?))
+}
+
+/// The keys the dashboard reacts to.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Key {
+    Up,
+    Down,
+    Left,
+    Right,
+    Enter,
+    PageUp,
+    PageDown,
+    Quit,
+}
+
+impl Key {
+    /// Reads the next known key from a raw terminal input.
+    pub fn read(input: &mut impl Read) -> io::Result<Option<Self>> {
+        while let Some(byte) = read_byte(input)? {
+            if let Some(key) = Self::decode(byte, input)? {
+                return Ok(Some(key));
+            }
+        }
+        Ok(None)
+    }
+
+    /// The key starting with `byte`, if it is known, reading the rest of an
+    /// escape sequence from `input`.
+    fn decode(byte: u8, input: &mut impl Read) -> io::Result<Option<Self>> {
+        let key = match byte {
+            b'q' | CTRL_C => Key::Quit,
+            b'k' => Key::Up,
+            b'j' => Key::Down,
+            b'h' => Key::Left,
+            b'l' => Key::Right,
+            b'\n' | b'\r' => Key::Enter,
+            ESC => return read_escape(input),
+            _ => return Ok(None),
+        };
+        Ok(Some(key))
+    }
+}
+
+/// Ctrl-C, read as a byte since `run` turns signals off.
+const CTRL_C: u8 = 0x03;
+const ESC: u8 = 0x1b;
+
+fn read_byte(input: &mut impl Read) -> io::Result<Option<u8>> {
+    let mut byte = [0; 1];
+    Ok((input.read(&mut byte)? == 1).then_some(byte[0]))
+}
+
+/// Reads the rest of an escape sequence a byte at a time. The first byte
+/// which cannot continue it is decoded as a key of its own, so that a bare
+/// Esc does not swallow the keys typed after it.
+fn read_escape(input: &mut impl Read) -> io::Result<Option<Key>> {
+    let Some(byte) = read_byte(input)? else {
+        return Ok(None);
+    };
+    if byte != b'[' {
+        return Key::decode(byte, input);
+    }
+    let Some(byte) = read_byte(input)? else {
+        return Ok(None);
+    };
+    let key = match byte {
+        b'A' => Key::Up,
+        b'B' => Key::Down,
+        b'C' => Key::Right,
+        b'D' => Key::Left,
+        b'5' | b'6' => {
+            let key = if byte == b'5' {
+                Key::PageUp
+            } else {
+                Key::PageDown
+            };
+            match read_byte(input)? {
+                Some(b'~') => key,
+                Some(byte) => return Key::decode(byte, input),
+                None => return Ok(None),
+            }
+        }
+        _ => return Ok(None),
+    };
+    Ok(Some(key))
+}
+
+/// How a run compares to the expected answer of its input.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Check {
+    Ok,
+    Wrong { expected: String },
+    Unchecked,
+    Failed(RunError),
+}
+
+impl Check {
+    fn label(&self) -> &'static str {
+        match self {
+            Check::Ok => "ok",
+            Check::Wrong { .. } => "WRONG",
+            Check::Unchecked => "?",
+            Check::Failed(_) => "FAILED",
+        }
+    }
+}
+
+/// The result of running a solution against one of its inputs.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct RunResult {
+    pub input: String,
+    pub outcome: Option<Outcome>,
+    pub check: Check,
+}
+
+/// A solution listed in the dashboard, with its inputs and last result.
+pub struct Entry<'a> {
+    pub solution: &'a Solution,
+    pub inputs: Vec<NamedInput>,
+    pub input: usize,
+    pub result: Option<RunResult>,
+}
+
+/// The dashboard state: the solutions, the selection and the trace pane.
+pub struct App<'a> {
+    pub entries: Vec<Entry<'a>>,
+    pub selected: usize,
+    pub trace: Vec<String>,
+    pub scroll: usize,
+    pub quit: bool,
+}
+
+const LIST_WIDTH: usize = 34;
+
+impl<'a> App<'a> {
+    /// Creates the dashboard over `solutions`, each with its day's inputs.
+    pub fn new(solutions: &'a [Solution]) -> Self {
+        let entries = solutions
+            .iter()
+            .map(|solution| Entry {
+                solution,
+                inputs: input::inputs(&solution.day_name()).unwrap_or_default(),
+                input: 0,
+                result: None,
+            })
+            .collect();
+
+        Self::with_entries(entries)
+    }
+
+    pub fn with_entries(entries: Vec<Entry<'a>>) -> Self {
+        Self {
+            entries,
+            selected: 0,
+            trace: Vec::new(),
+            scroll: 0,
+            quit: false,
+        }
+    }
+
+    pub fn handle(&mut self, key: Key) {
+        let last = self.entries.len().saturating_sub(1);
+        match key {
+            Key::Up => self.selected = self.selected.saturating_sub(1),
+            Key::Down => self.selected = (self.selected + 1).min(last),
+            Key::Left | Key::Right => {
+                if let Some(entry) = self.entries.get_mut(self.selected) {
+                    let n = entry.inputs.len().max(1);
+                    entry.input = match key {
+                        Key::Left => (entry.input + n - 1) % n,
+                        _ => (entry.input + 1) % n,
+                    };
+                }
+            }
+            Key::Enter => self.run_selected(),
+            Key::PageUp => self.scroll = self.scroll.saturating_sub(10),
+            Key::PageDown => {
+                self.scroll = (self.scroll + 10).min(self.trace.len().saturating_sub(1))
+            }
+            Key::Quit => self.quit = true,
+        }
+    }
+
+    /// Runs the selected solution against its selected input, collecting
+    /// everything it traces.
+    pub fn run_selected(&mut self) {
+        let Some(entry) = self.entries.get_mut(self.selected) else {
+            return;
+        };
+        let Some(input) = entry.inputs.get(entry.input) else {
+            return;
+        };
+
+        let (result, lines) = trace::capture(|| entry.solution.run(&input.text));
+        let expected = input.answers.get(entry.solution.part);
+        let (outcome, check) = match result {
+            Ok(outcome) => {
+                let check = match expected {
+                    Some(expected) if expected == outcome.answer => Check::Ok,
+                    Some(expected) => Check::Wrong {
+                        expected: expected.to_string(),
+                    },
+                    None => Check::Unchecked,
+                };
+                (Some(outcome), check)
+            }
+            Err(e) => (None, Check::Failed(e)),
+        };
+
+        self.trace = lines;
+        self.scroll = 0;
+        entry.result = Some(RunResult {
+            input: input.name.clone(),
+            outcome,
+            check,
+        });
+    }
+
+    pub fn render(&self, buffer: &mut Buffer) {
+        let (width, height) = (buffer.width, buffer.height);
+        buffer.put(
+            0,
+            0,
+            &format!(
+                "Advent of code {} | up/down: select  left/right: input  enter: run  pgup/pgdn: scroll  q: quit",
+                YEAR
+            ),
+        );
+
+        let list_height = height.saturating_sub(1);
+        let first = self.selected.saturating_sub(list_height.saturating_sub(1));
+        for (row, (i, entry)) in self
+            .entries
+            .iter()
+            .enumerate()
+            .skip(first)
+            .take(list_height)
+            .enumerate()
+        {
+            let marker = if i == self.selected { '>' } else { ' ' };
+            let status = entry.result.as_ref().map(|r| r.check.label()).unwrap_or("");
+            let line = format!("{} {} {}", marker, entry.solution, status);
+            let line: String = line.chars().take(LIST_WIDTH - 1).collect();
+            buffer.put(0, row + 1, &line);
+        }
+
+        for y in 1..height {
+            buffer.put(LIST_WIDTH, y, "|");
+        }
+
+        let x = LIST_WIDTH + 2;
+        let mut y = 1;
+        if let Some(entry) = self.entries.get(self.selected) {
+            let input = entry
+                .inputs
+                .get(entry.input)
+                .map(|input| input.name.as_str())
+                .unwrap_or("(no input)");
+            let mut details = vec![
+                entry.solution.to_string(),
+                format!(
+                    "Input: {} ({}/{})",
+                    input,
+                    entry.input + 1,
+                    entry.inputs.len()
+                ),
+            ];
+
+            if let Some(result) = &entry.result {
+                details.push(format!("Last run on: {}", result.input));
+                if let Some(outcome) = &result.outcome {
+                    details.push(format!("Answer: {}", outcome.answer));
+                    details.push(format!("Generator: {:?}", outcome.generator));
+                    details.push(format!("Runner: {:?}", outcome.runner));
+                }
+                details.push(match &result.check {
+                    Check::Ok => "Check: ok".to_string(),
+                    Check::Wrong { expected } => format!("Check: WRONG, expected {}", expected),
+                    Check::Unchecked => "Check: no expected answer".to_string(),
+                    Check::Failed(e) => format!("Check: {}", e),
+                });
+            }
+
+            for line in details {
+                buffer.put(x, y, &line);
+                y += 1;
+            }
+        }
+
+        y += 1;
+        buffer.put(x, y, &format!("{:-<1$}", "Trace ", width.saturating_sub(x)));
+        y += 1;
+        for line in self.trace.iter().skip(self.scroll) {
+            if y >= height {
+                break;
+            }
+            buffer.put(x, y, line);
+            y += 1;
+        }
+    }
+
+    pub fn draw(&self, backend: &mut impl Backend) -> io::Result<()> {
+        let (width, height) = backend.size();
+        let mut buffer = Buffer::new(width, height);
+        self.render(&mut buffer);
+        backend.draw(&buffer)
+    }
+}
+
+/// Draws the dashboard and reacts to `keys` until it is asked to quit.
+pub fn event_loop(
+    app: &mut App,
+    backend: &mut impl Backend,
+    mut keys: impl FnMut() -> io::Result<Option<Key>>,
+) -> io::Result<()> {
+    app.draw(backend)?;
+    while !app.quit {
+        let Some(key) = keys()? else {
+            break;
+        };
+        app.handle(key);
+        app.draw(backend)?;
+    }
+    Ok(())
+}
+
+/// Runs the dashboard on the current terminal, over every solution.
+pub fn run() -> io::Result<()> {
+    let saved = stty(&["-g"])?;
+    // Without `isig`, Ctrl-C arrives as a key instead of killing the process
+    // before the terminal is restored.
+    stty(&["-icanon", "-echo", "-isig", "min", "1"])?;
+    let _restore = RestoreTerminal(saved);
+    write!(io::stdout(), "\x1b[?25l")?;
+
+    let mut app = App::new(SOLUTIONS);
+    let mut backend = TerminalBackend::new(io::stdout());
+    let mut stdin = io::stdin();
+    event_loop(&mut app, &mut backend, || Key::read(&mut stdin))
+}
+
+/// Puts back the saved `stty` settings and the cursor when dropped, so that
+/// errors and panics leave a usable terminal too.
+struct RestoreTerminal(String);
+
+impl Drop for RestoreTerminal {
+    fn drop(&mut self) {
+        let mut stdout = io::stdout();
+        let _ = write!(stdout, "\x1b[?25h\x1b[H\x1b[2J");
+        let _ = stdout.flush();
+        let _ = stty(&[self.0.trim()]);
+    }
+}
+
+fn stty(args: &[&str]) -> io::Result<String> {
+    let output = Command::new("stty")
+        .args(args)
+        .stdin(Stdio::inherit())
+        .output()?;
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{error::Error, time::Duration};
+
+    use aoc_runner::{ArcStr, Runner};
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::*;
+    use crate::input::Answers;
+
+    struct Echo(ArcStr);
+
+    impl Runner for Echo {
+        fn gen(input: ArcStr) -> Self {
+            Echo(input)
+        }
+
+        fn run(&self) -> Box<dyn std::fmt::Display> {
+            let input: &str = std::borrow::Borrow::borrow(&self.0);
+            crate::trace!("echoing {}", input);
+            crate::trace!("done");
+            Box::new(input.to_string())
+        }
+
+        fn bench(&self, _: fn(&dyn std::fmt::Display)) {}
+    }
+
+    fn echo(input: ArcStr) -> Result<Box<dyn Runner>, Box<dyn Error>> {
+        Ok(Box::new(Echo::gen(input)))
+    }
+
+    fn panics(_: ArcStr) -> Result<Box<dyn Runner>, Box<dyn Error>> {
+        panic!("bad input")
+    }
+
+    static SOLUTIONS: &[Solution] = &[
+        Solution {
+            day: 1,
+            part: 1,
+            variant: None,
+            factory: echo,
+            placeholder: false,
+            source: "",
+        },
+        Solution {
+            day: 1,
+            part: 2,
+            variant: Some("Broken"),
+            factory: panics,
+            placeholder: false,
+            source: "",
+        },
+    ];
+
+    fn named_input(name: &str, text: &str, part1: Option<&str>) -> NamedInput {
+        NamedInput {
+            name: name.to_string(),
+            path: Default::default(),
+            text: text.to_string(),
+            answers: Answers {
+                part1: part1.map(str::to_string),
+                part2: None,
+            },
+        }
+    }
+
+    fn app() -> App<'static> {
+        let inputs = vec![
+            named_input("alice", "42", Some("42")),
+            named_input("bob", "7", Some("8")),
+        ];
+        App::with_entries(
+            SOLUTIONS
+                .iter()
+                .map(|solution| Entry {
+                    solution,
+                    inputs: inputs.clone(),
+                    input: 0,
+                    result: None,
+                })
+                .collect(),
+        )
+    }
+
+    fn without_timings(mut app: App) -> App {
+        for entry in &mut app.entries {
+            if let Some(outcome) = entry.result.as_mut().and_then(|r| r.outcome.as_mut()) {
+                outcome.generator = Duration::ZERO;
+                outcome.runner = Duration::ZERO;
+            }
+        }
+        app
+    }
+
+    fn screen(app: &App) -> Vec<String> {
+        let mut backend = TestBackend::new(80, 12);
+        app.draw(&mut backend).unwrap();
+        backend.buffer.lines()
+    }
+
+    #[test]
+    fn buffer_clips_text() {
+        let mut buffer = Buffer::new(4, 2);
+        buffer.put(2, 0, "abc");
+        buffer.put(0, 5, "ignored");
+
+        assert_eq!(buffer.lines(), vec!["  ab", ""]);
+    }
+
+    /// Splits a screen line into the list and the details panes.
+    fn panes(line: &str) -> (&str, &str) {
+        let (list, details) = line.split_at(LIST_WIDTH);
+        (list.trim_end(), details.trim_start_matches('|').trim())
+    }
+
+    #[test]
+    fn render_initial_screen() {
+        let lines = screen(&app());
+
+        assert!(lines[0].starts_with("Advent of code 2015 |"));
+        assert_eq!(panes(&lines[1]), ("> Day 1 - Part 1", "Day 1 - Part 1"));
+        assert_eq!(
+            panes(&lines[2]),
+            ("  Day 1 - Part 2 - Broken", "Input: alice (1/2)")
+        );
+        assert_eq!(panes(&lines[4]).1, format!("Trace {}", "-".repeat(38)));
+    }
+
+    #[test]
+    fn run_and_check_answer() {
+        let mut app = app();
+        app.handle(Key::Enter);
+        let lines = screen(&without_timings(app));
+
+        assert_eq!(panes(&lines[1]), ("> Day 1 - Part 1 ok", "Day 1 - Part 1"));
+        assert_eq!(panes(&lines[3]).1, "Last run on: alice");
+        assert_eq!(panes(&lines[4]).1, "Answer: 42");
+        assert_eq!(panes(&lines[5]).1, "Generator: 0ns");
+        assert_eq!(panes(&lines[7]).1, "Check: ok");
+        assert_eq!(panes(&lines[10]).1, "echoing 42");
+        assert_eq!(panes(&lines[11]).1, "done");
+    }
+
+    #[test]
+    fn run_other_input() {
+        let mut app = app();
+        app.handle(Key::Right);
+        app.handle(Key::Enter);
+
+        let result = app.entries[0].result.as_ref().unwrap();
+        assert_eq!(result.input, "bob");
+        assert_eq!(
+            result.check,
+            Check::Wrong {
+                expected: "8".to_string()
+            }
+        );
+
+        app.handle(Key::Left);
+        app.handle(Key::Left);
+        assert_eq!(app.entries[0].input, 1);
+    }
+
+    #[test]
+    fn run_failing_solution() {
+        let mut app = app();
+        app.handle(Key::Down);
+        app.handle(Key::Down);
+        app.handle(Key::Enter);
+
+        assert_eq!(app.selected, 1);
+        let lines = screen(&app);
+        assert_eq!(
+            panes(&lines[2]),
+            ("> Day 1 - Part 2 - Broken FAILED", "Input: alice (1/2)")
+        );
+        assert_eq!(
+            panes(&lines[4]).1,
+            "Check: FAILED while generating: bad input"
+        );
+    }
+
+    #[test]
+    fn scroll_trace() {
+        let mut app = app();
+        app.trace = (0..30).map(|i| format!("line {}", i)).collect();
+
+        app.handle(Key::PageDown);
+        assert_eq!(panes(&screen(&app)[5]).1, "line 10");
+
+        app.handle(Key::PageDown);
+        app.handle(Key::PageDown);
+        assert_eq!(app.scroll, 29);
+
+        app.handle(Key::PageUp);
+        assert_eq!(app.scroll, 19);
+    }
+
+    #[test]
+    fn event_loop_until_quit() {
+        let mut app = app();
+        let mut backend = TestBackend::new(80, 12);
+        let mut keys = vec![Key::Quit, Key::Enter, Key::Down].into_iter();
+
+        event_loop(&mut app, &mut backend, || Ok(keys.next_back())).unwrap();
+
+        assert!(app.quit);
+        assert_eq!(app.selected, 1);
+        assert!(backend.buffer.lines()[2].contains("FAILED"));
+    }
+
+    #[rstest]
+    #[case(b"\x1bj", vec![Key::Down])]
+    #[case(b"\x1b\x1b[A", vec![Key::Up])]
+    #[case(b"\x1bkq", vec![Key::Up, Key::Quit])]
+    #[case(b"\x1b[5j", vec![Key::Down])]
+    #[case(b"j\x1b", vec![Key::Down])]
+    fn read_bare_escape(#[case] mut input: &[u8], #[case] expected: Vec<Key>) {
+        let mut keys = Vec::new();
+        while let Some(key) = Key::read(&mut input).unwrap() {
+            keys.push(key);
+        }
+        assert_eq!(keys, expected);
+    }
+
+    #[test]
+    fn read_keys() {
+        let mut input: &[u8] = b"jx\x1b[A\x1b[6~\rq\x03";
+        let mut keys = Vec::new();
+        while let Some(key) = Key::read(&mut input).unwrap() {
+            keys.push(key);
+        }
+
+        assert_eq!(
+            keys,
+            vec![
+                Key::Down,
+                Key::Up,
+                Key::PageDown,
+                Key::Enter,
+                Key::Quit,
+                Key::Quit
+            ]
+        );
+    }
+}
@@ -0,0 +1,530 @@
+//! A small register machine, for the puzzles handing out programs in a toy
+//! assembly: 2015's Turing lock (`hlf`, `tpl`, `inc`, `jmp`, `jie`, `jio`)
+//! and 2016's assembunny (`cpy`, `inc`, `dec`, `jnz`).
+//!
+//! # Examples
+//!
+//! ```rust
+//! use aoc_2015::vm::{parse_program, Machine};
+//!
+//! let program = parse_program("inc a\njio a, +2\ntpl a\ninc a").unwrap();
+//! let mut machine = Machine::new(program);
+//! assert_eq!(machine.run(), Ok(3));
+//! assert_eq!(machine.registers().get("a"), 2);
+//! assert!(machine.is_halted());
+//! ```
+
+use std::{collections::BTreeMap, fmt};
+
+use derive_more::derive::{Display, Error};
+use nom::{
+    branch::alt,
+    bytes::complete::tag,
+    character::complete::space1,
+    combinator::map,
+    sequence::{preceded, separated_pair},
+};
+
+use crate::{
+    error::Result,
+    parse::{identifier, parse_lines, signed, ParseResult},
+};
+
+/// A register, or a value written in the program.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Operand {
+    Register(String),
+    Value(i64),
+}
+
+/// An instruction of any of the dialects. Jump offsets are relative to the
+/// jump itself.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Instruction {
+    /// Halves a register, rounding down.
+    Hlf {
+        register: String,
+    },
+    /// Triples a register.
+    Tpl {
+        register: String,
+    },
+    Inc {
+        register: String,
+    },
+    Dec {
+        register: String,
+    },
+    /// Copies a register or a value into a register.
+    Cpy {
+        source: Operand,
+        register: String,
+    },
+    Jmp {
+        offset: Operand,
+    },
+    /// Jumps if a register is even.
+    Jie {
+        register: String,
+        offset: Operand,
+    },
+    /// Jumps if a register is one (not odd!).
+    Jio {
+        register: String,
+        offset: Operand,
+    },
+    /// Jumps unless a register or a value is zero.
+    Jnz {
+        condition: Operand,
+        offset: Operand,
+    },
+}
+
+/// Parses one instruction per line.
+pub fn parse_program(input: &str) -> Result<Vec<Instruction>> {
+    parse_lines(input, parse_instruction)
+}
+
+pub fn parse_instruction(input: &str) -> ParseResult<'_, Instruction> {
+    alt((
+        parse_hlf, parse_tpl, parse_inc, parse_dec, parse_cpy, parse_jmp, parse_jie, parse_jio,
+        parse_jnz,
+    ))(input)
+}
+
+fn parse_hlf(input: &str) -> ParseResult<'_, Instruction> {
+    let (input, register) = preceded(tag("hlf "), parse_register)(input)?;
+    Ok((input, Instruction::Hlf { register }))
+}
+
+fn parse_tpl(input: &str) -> ParseResult<'_, Instruction> {
+    let (input, register) = preceded(tag("tpl "), parse_register)(input)?;
+    Ok((input, Instruction::Tpl { register }))
+}
+
+fn parse_inc(input: &str) -> ParseResult<'_, Instruction> {
+    let (input, register) = preceded(tag("inc "), parse_register)(input)?;
+    Ok((input, Instruction::Inc { register }))
+}
+
+fn parse_dec(input: &str) -> ParseResult<'_, Instruction> {
+    let (input, register) = preceded(tag("dec "), parse_register)(input)?;
+    Ok((input, Instruction::Dec { register }))
+}
+
+fn parse_cpy(input: &str) -> ParseResult<'_, Instruction> {
+    let (input, (source, register)) = preceded(
+        tag("cpy "),
+        separated_pair(parse_operand, parse_separator, parse_register),
+    )(input)?;
+    Ok((input, Instruction::Cpy { source, register }))
+}
+
+fn parse_jmp(input: &str) -> ParseResult<'_, Instruction> {
+    let (input, offset) = preceded(tag("jmp "), parse_operand)(input)?;
+    Ok((input, Instruction::Jmp { offset }))
+}
+
+fn parse_jie(input: &str) -> ParseResult<'_, Instruction> {
+    let (input, (register, offset)) = preceded(
+        tag("jie "),
+        separated_pair(parse_register, parse_separator, parse_operand),
+    )(input)?;
+    Ok((input, Instruction::Jie { register, offset }))
+}
+
+fn parse_jio(input: &str) -> ParseResult<'_, Instruction> {
+    let (input, (register, offset)) = preceded(
+        tag("jio "),
+        separated_pair(parse_register, parse_separator, parse_operand),
+    )(input)?;
+    Ok((input, Instruction::Jio { register, offset }))
+}
+
+fn parse_jnz(input: &str) -> ParseResult<'_, Instruction> {
+    let (input, (condition, offset)) = preceded(
+        tag("jnz "),
+        separated_pair(parse_operand, parse_separator, parse_operand),
+    )(input)?;
+    Ok((input, Instruction::Jnz { condition, offset }))
+}
+
+/// 2015 separates operands with a comma, 2016 with spaces only.
+fn parse_separator(input: &str) -> ParseResult<'_, &str> {
+    alt((tag(", "), space1))(input)
+}
+
+fn parse_register(input: &str) -> ParseResult<'_, String> {
+    map(identifier, str::to_string)(input)
+}
+
+fn parse_operand(input: &str) -> ParseResult<'_, Operand> {
+    alt((
+        map(signed, Operand::Value),
+        map(parse_register, Operand::Register),
+    ))(input)
+}
+
+impl fmt::Display for Operand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Operand::Register(register) => write!(f, "{}", register),
+            Operand::Value(value) => write!(f, "{:+}", value),
+        }
+    }
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Instruction::Hlf { register } => write!(f, "hlf {}", register),
+            Instruction::Tpl { register } => write!(f, "tpl {}", register),
+            Instruction::Inc { register } => write!(f, "inc {}", register),
+            Instruction::Dec { register } => write!(f, "dec {}", register),
+            Instruction::Cpy { source, register } => write!(f, "cpy {} {}", source, register),
+            Instruction::Jmp { offset } => write!(f, "jmp {}", offset),
+            Instruction::Jie { register, offset } => write!(f, "jie {}, {}", register, offset),
+            Instruction::Jio { register, offset } => write!(f, "jio {}, {}", register, offset),
+            Instruction::Jnz { condition, offset } => write!(f, "jnz {} {}", condition, offset),
+        }
+    }
+}
+
+/// Registers by name. A register nothing wrote to yet holds 0.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Registers(BTreeMap<String, i64>);
+
+impl Registers {
+    pub fn get(&self, register: &str) -> i64 {
+        self.0.get(register).copied().unwrap_or(0)
+    }
+
+    pub fn set(&mut self, register: &str, value: i64) {
+        match self.0.get_mut(register) {
+            Some(slot) => *slot = value,
+            None => {
+                self.0.insert(register.to_string(), value);
+            }
+        }
+    }
+
+    /// The registers written to so far, by name.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, i64)> {
+        self.0.iter().map(|(name, &value)| (name.as_str(), value))
+    }
+
+    /// Leaves the register as it was if `f` finds no value.
+    fn update(&mut self, register: &str, f: impl FnOnce(i64) -> Option<i64>) -> Option<()> {
+        let value = f(self.get(register))?;
+        self.set(register, value);
+        Some(())
+    }
+
+    fn value(&self, operand: &Operand) -> i64 {
+        match operand {
+            Operand::Register(register) => self.get(register),
+            Operand::Value(value) => *value,
+        }
+    }
+}
+
+impl fmt::Display for Registers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, (name, value)) in self.iter().enumerate() {
+            if i > 0 {
+                write!(f, " ")?;
+            }
+            write!(f, "{}={}", name, value)?;
+        }
+        Ok(())
+    }
+}
+
+/// Why the machine stopped before halting, with the index of the instruction
+/// it could not run.
+#[derive(Debug, Clone, PartialEq, Eq, Display, Error)]
+pub enum Fault {
+    /// The machine ran as many instructions as it was allowed to.
+    #[display("no halt after {limit} instructions, at {pc}")]
+    LimitExceeded { limit: u64, pc: i64 },
+    /// A register or the program counter would leave the range of `i64`.
+    #[display("arithmetic overflow at {pc}")]
+    Overflow { pc: i64 },
+}
+
+/// Runs a program from its first instruction, until it jumps out of it.
+#[derive(Debug, Clone)]
+pub struct Machine {
+    program: Vec<Instruction>,
+    registers: Registers,
+    pc: i64,
+    steps: u64,
+    limit: Option<u64>,
+    trace: bool,
+}
+
+impl Machine {
+    pub fn new(program: Vec<Instruction>) -> Self {
+        Self {
+            program,
+            registers: Registers::default(),
+            pc: 0,
+            steps: 0,
+            limit: None,
+            trace: false,
+        }
+    }
+
+    /// Stops [`Machine::step`] and [`Machine::run`] after `limit`
+    /// instructions, for programs that may never halt.
+    pub fn limit(mut self, limit: u64) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Traces each instruction run, with the registers after it.
+    pub fn trace(mut self, trace: bool) -> Self {
+        self.trace = trace;
+        self
+    }
+
+    pub fn registers(&self) -> &Registers {
+        &self.registers
+    }
+
+    /// For setting registers up before a run.
+    pub fn registers_mut(&mut self) -> &mut Registers {
+        &mut self.registers
+    }
+
+    /// The index of the next instruction.
+    pub fn pc(&self) -> i64 {
+        self.pc
+    }
+
+    /// How many instructions were run so far.
+    pub fn steps(&self) -> u64 {
+        self.steps
+    }
+
+    /// Whether the program counter left the program.
+    pub fn is_halted(&self) -> bool {
+        self.index().is_none()
+    }
+
+    /// Runs the next instruction. Returns whether there was one.
+    ///
+    /// On a fault, the machine is left as it was before the instruction.
+    pub fn step(&mut self) -> std::result::Result<bool, Fault> {
+        let Some(index) = self.index() else {
+            return Ok(false);
+        };
+        if let Some(limit) = self.limit.filter(|&limit| self.steps >= limit) {
+            return Err(Fault::LimitExceeded { limit, pc: self.pc });
+        }
+
+        let pc = self.pc;
+        let instruction = &self.program[index];
+        let registers = &mut self.registers;
+        let offset = match instruction {
+            Instruction::Hlf { register } => registers
+                .update(register, |value| Some(value.div_euclid(2)))
+                .map(|()| 1),
+            Instruction::Tpl { register } => registers
+                .update(register, |value| value.checked_mul(3))
+                .map(|()| 1),
+            Instruction::Inc { register } => registers
+                .update(register, |value| value.checked_add(1))
+                .map(|()| 1),
+            Instruction::Dec { register } => registers
+                .update(register, |value| value.checked_sub(1))
+                .map(|()| 1),
+            Instruction::Cpy { source, register } => {
+                let value = registers.value(source);
+                registers.set(register, value);
+                Some(1)
+            }
+            Instruction::Jmp { offset } => Some(registers.value(offset)),
+            Instruction::Jie { register, offset } if registers.get(register) % 2 == 0 => {
+                Some(registers.value(offset))
+            }
+            Instruction::Jio { register, offset } if registers.get(register) == 1 => {
+                Some(registers.value(offset))
+            }
+            Instruction::Jnz { condition, offset } if registers.value(condition) != 0 => {
+                Some(registers.value(offset))
+            }
+            Instruction::Jie { .. } | Instruction::Jio { .. } | Instruction::Jnz { .. } => Some(1),
+        };
+        self.pc = offset
+            .and_then(|offset| pc.checked_add(offset))
+            .ok_or(Fault::Overflow { pc })?;
+        self.steps += 1;
+
+        if self.trace {
+            crate::trace!(
+                "{:>4}  {:<14}{}",
+                pc,
+                instruction.to_string(),
+                self.registers
+            );
+        }
+        Ok(true)
+    }
+
+    /// Runs until the program halts. Returns the number of instructions run,
+    /// since the start.
+    pub fn run(&mut self) -> std::result::Result<u64, Fault> {
+        while self.step()? {}
+        Ok(self.steps)
+    }
+
+    /// The index of the next instruction in the program, if there is one.
+    fn index(&self) -> Option<usize> {
+        usize::try_from(self.pc)
+            .ok()
+            .filter(|&index| index < self.program.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::*;
+    use crate::{error::Error, parse::parse_all, trace};
+
+    fn register(name: &str) -> Operand {
+        Operand::Register(name.to_string())
+    }
+
+    #[rstest]
+    #[case("hlf a", Instruction::Hlf { register: "a".to_string() })]
+    #[case("jmp -7", Instruction::Jmp { offset: Operand::Value(-7) })]
+    #[case("jie b, +4", Instruction::Jie { register: "b".to_string(), offset: Operand::Value(4) })]
+    #[case("cpy 41 a", Instruction::Cpy { source: Operand::Value(41), register: "a".to_string() })]
+    #[case("cpy c d", Instruction::Cpy { source: register("c"), register: "d".to_string() })]
+    #[case("jnz 1 c", Instruction::Jnz { condition: Operand::Value(1), offset: register("c") })]
+    fn parse_both_dialects(#[case] line: &str, #[case] expected: Instruction) {
+        assert_eq!(parse_all(line, parse_instruction).unwrap(), expected);
+    }
+
+    #[rstest]
+    #[case("tpl a")]
+    #[case("jio a, +2")]
+    #[case("jie a, -1")]
+    #[case("cpy +3 b")]
+    #[case("jnz a -2")]
+    fn display_parses_back(#[case] line: &str) {
+        let instruction = parse_all(line, parse_instruction).unwrap();
+        assert_eq!(instruction.to_string(), line);
+    }
+
+    #[test]
+    fn invalid_instruction() {
+        let error = parse_program("inc a\nmul a, b").unwrap_err();
+        assert!(matches!(error, Error::Parse { line: 2, .. }), "{}", error);
+    }
+
+    #[test]
+    fn turing_lock_example() {
+        let mut machine = Machine::new(parse_program("inc a\njio a, +2\ntpl a\ninc a").unwrap());
+        assert_eq!(machine.run(), Ok(3));
+        assert_eq!(machine.registers().to_string(), "a=2");
+        assert_eq!(machine.pc(), 4);
+    }
+
+    #[test]
+    fn assembunny_example() {
+        let program = parse_program("cpy 41 a\ninc a\ninc a\ndec a\njnz a 2\ndec a").unwrap();
+        let mut machine = Machine::new(program);
+        assert_eq!(machine.run(), Ok(5));
+        assert_eq!(machine.registers().get("a"), 42);
+    }
+
+    #[test]
+    fn counts_down_from_preset_register() {
+        let program = parse_program("jnz n 2\njmp +4\ndec n\ninc acc\njmp -4").unwrap();
+        let mut machine = Machine::new(program);
+        machine.registers_mut().set("n", 3);
+        machine.run().unwrap();
+        assert_eq!(machine.registers().get("acc"), 3);
+        assert_eq!(machine.registers().get("n"), 0);
+    }
+
+    #[test]
+    fn step_by_step() {
+        let mut machine = Machine::new(parse_program("inc a\njmp -1").unwrap());
+        assert_eq!(machine.step(), Ok(true));
+        assert_eq!((machine.pc(), machine.registers().get("a")), (1, 1));
+        assert_eq!(machine.step(), Ok(true));
+        assert_eq!(machine.pc(), 0);
+        assert_eq!(machine.steps(), 2);
+    }
+
+    #[test]
+    fn jumps_before_the_start_halt() {
+        let mut machine = Machine::new(parse_program("jmp -1").unwrap());
+        assert_eq!(machine.run(), Ok(1));
+        assert!(machine.is_halted());
+        assert_eq!(machine.step(), Ok(false));
+    }
+
+    #[test]
+    fn limit_stops_infinite_loop() {
+        let mut machine = Machine::new(parse_program("inc a\njmp -1").unwrap()).limit(5);
+        assert_eq!(machine.run(), Err(Fault::LimitExceeded { limit: 5, pc: 1 }));
+        assert_eq!(machine.registers().get("a"), 3);
+        assert!(!machine.is_halted());
+    }
+
+    #[rstest]
+    #[case(7, 3)]
+    #[case(-3, -2)]
+    #[case(-1, -1)]
+    fn hlf_rounds_down(#[case] value: i64, #[case] expected: i64) {
+        let mut machine = Machine::new(parse_program("hlf a").unwrap());
+        machine.registers_mut().set("a", value);
+        machine.run().unwrap();
+        assert_eq!(machine.registers().get("a"), expected);
+    }
+
+    #[rstest]
+    #[case("inc a", i64::MAX)]
+    #[case("dec a", i64::MIN)]
+    #[case("tpl a", i64::MAX / 2)]
+    #[case("tpl a", i64::MIN / 3 - 1)]
+    fn register_overflow(#[case] line: &str, #[case] value: i64) {
+        let mut machine = Machine::new(parse_program(&format!("jmp +1\n{}", line)).unwrap());
+        machine.registers_mut().set("a", value);
+        assert_eq!(machine.run(), Err(Fault::Overflow { pc: 1 }));
+        assert_eq!(machine.registers().get("a"), value);
+        assert_eq!((machine.pc(), machine.steps()), (1, 1));
+    }
+
+    #[test]
+    fn jump_overflow() {
+        let mut machine = Machine::new(parse_program("inc a\njnz 1 j").unwrap());
+        machine.registers_mut().set("j", i64::MAX);
+        assert_eq!(machine.run(), Err(Fault::Overflow { pc: 1 }));
+        assert_eq!(
+            machine.run().unwrap_err().to_string(),
+            "arithmetic overflow at 1"
+        );
+    }
+
+    #[test]
+    fn trace_each_step() {
+        let program = parse_program("inc a\njio a, +2\ntpl a\ninc a").unwrap();
+        let (_, lines) = trace::capture(|| Machine::new(program).trace(true).run());
+        assert_eq!(
+            lines,
+            [
+                "   0  inc a         a=1",
+                "   1  jio a, +2     a=1",
+                "   3  inc a         a=2",
+            ]
+        );
+    }
+}